@@ -3,11 +3,22 @@ use eframe::egui;
 use std::sync::{Arc, Mutex};
 
 mod dsp;
+mod midi;
 mod settings;
 mod stream;
 mod ui;
 
+use dsp::convolution::ImpulseResponse;
+use dsp::envelope_filter::FilterMode;
+use dsp::lfo::LfoShape;
+use dsp::looper::{Looper, LooperSlot, SharedLooper};
 use dsp::params::SharedParams;
+use dsp::pitch_shift::{PitchMode, Scale};
+use dsp::shimmer_reverb::ShimmerInterval;
+use dsp::stereo_delay::{DelayMode, MAX_TAPS};
+use dsp::univibe::VibeMode;
+use dsp::volume_pedal::Taper;
+use dsp::wah::WahRange;
 
 pub struct GooseDsp {
     available_devices: Vec<String>,
@@ -23,6 +34,11 @@ pub struct GooseDsp {
     input_volume: f32,
     output_volume: f32,
     output_level: Arc<Mutex<f32>>,
+    tuner_pitch: Arc<Mutex<Option<f32>>>,
+    tuner_a4: f32,
+    tuner_strobe: bool,
+    tuner_strobe_phase: f32,
+    looper: SharedLooper,
     overdrive_enabled: bool,
     overdrive_gain: f32,
     error_message: Option<String>,
@@ -33,7 +49,169 @@ pub struct GooseDsp {
     eq_high: f32,
     gate_enabled: bool,
     gate_threshold: f32,
+    wah_enabled: bool,
+    wah_position: f32,
+    wah_range: WahRange,
+    wah_q: f32,
+    wah_cc: u8,
+    midi_ports: Vec<String>,
+    selected_midi_port: Option<String>,
+    midi_connection: Option<midir::MidiInputConnection<()>>,
+    envelope_filter_enabled: bool,
+    envelope_filter_mode: FilterMode,
+    envelope_filter_sensitivity: f32,
+    envelope_filter_range: f32,
+    envelope_filter_q: f32,
+    envelope_filter_attack: f32,
+    envelope_filter_decay: f32,
+    envelope_filter_down: bool,
+    envelope_filter_lfo: bool,
+    envelope_filter_rate: f32,
+    octaver_enabled: bool,
+    octaver_dry: f32,
+    octaver_sub: f32,
+    octaver_sub2: f32,
+    octaver_up: f32,
+    pitch_enabled: bool,
+    pitch_mode: PitchMode,
+    pitch_semitones: i32,
+    pitch_cents: f32,
+    pitch_drop: i32,
+    pitch_mix: f32,
+    pitch_key: usize,
+    pitch_scale: Scale,
+    pitch_steps: i32,
+    ring_mod_enabled: bool,
+    ring_mod_shape: LfoShape,
+    ring_mod_frequency: f32,
+    ring_mod_fine: f32,
+    ring_mod_lfo_rate: f32,
+    ring_mod_lfo_depth: f32,
+    ring_mod_tracking: bool,
+    ring_mod_mix: f32,
+    boost_enabled: bool,
+    boost_gain: f32,
+    boost_tight: bool,
+    boost_post: bool,
+    bitcrusher_enabled: bool,
+    bitcrusher_bits: f32,
+    bitcrusher_rate: f32,
+    bitcrusher_anti_alias: bool,
+    bitcrusher_dither: bool,
+    bitcrusher_mix: f32,
+    volume_pedal_enabled: bool,
+    volume_pedal_position: f32,
+    volume_pedal_taper: Taper,
+    volume_pedal_minimum: f32,
     cabinet_enabled: bool,
+    tempo: f32,
+    tuner_enabled: bool,
+    tuner_mute: bool,
+    swell_enabled: bool,
+    swell_rise: f32,
+    swell_sensitivity: f32,
+    nam_enabled: bool,
+    nam_model_name: Option<String>,
+    chorus_enabled: bool,
+    chorus_rate: f32,
+    chorus_depth: f32,
+    chorus_delay: f32,
+    chorus_mix: f32,
+    chorus_spread: f32,
+    vibrato_enabled: bool,
+    vibrato_rate: f32,
+    vibrato_depth: f32,
+    univibe_enabled: bool,
+    univibe_rate: f32,
+    univibe_depth: f32,
+    univibe_mode: VibeMode,
+    flanger_enabled: bool,
+    flanger_rate: f32,
+    flanger_depth: f32,
+    flanger_manual: f32,
+    flanger_feedback: f32,
+    flanger_mix: f32,
+    flanger_through_zero: bool,
+    phaser_enabled: bool,
+    phaser_stages: usize,
+    phaser_rate: f32,
+    phaser_depth: f32,
+    phaser_feedback: f32,
+    phaser_centre: f32,
+    phaser_mix: f32,
+    phaser_sync: bool,
+    phaser_division: usize,
+    tremolo_enabled: bool,
+    tremolo_shape: LfoShape,
+    tremolo_rate: f32,
+    tremolo_depth: f32,
+    tremolo_sync: bool,
+    tremolo_division: usize,
+    tremolo_pan: bool,
+    tremolo_harmonic: bool,
+    rotary_enabled: bool,
+    rotary_fast: bool,
+    rotary_spread: f32,
+    tape_echo_enabled: bool,
+    tape_echo_time: f32,
+    tape_echo_heads: usize,
+    tape_echo_feedback: f32,
+    tape_echo_saturation: f32,
+    tape_echo_tone: f32,
+    tape_echo_wow: f32,
+    tape_echo_flutter: f32,
+    tape_echo_mix: f32,
+    bbd_enabled: bool,
+    bbd_time: f32,
+    bbd_feedback: f32,
+    bbd_mix: f32,
+    bbd_mod_rate: f32,
+    bbd_mod_depth: f32,
+    bbd_noise: f32,
+    stereo_delay_enabled: bool,
+    stereo_delay_mode: DelayMode,
+    stereo_delay_left: f32,
+    stereo_delay_right: f32,
+    stereo_delay_feedback: f32,
+    stereo_delay_mix: f32,
+    stereo_delay_sync: bool,
+    stereo_delay_left_division: usize,
+    stereo_delay_right_division: usize,
+    stereo_delay_taps: usize,
+    stereo_delay_tap_times: [f32; MAX_TAPS],
+    stereo_delay_tap_divisions: [usize; MAX_TAPS],
+    stereo_delay_tap_levels: [f32; MAX_TAPS],
+    stereo_delay_tap_pans: [f32; MAX_TAPS],
+    spring_enabled: bool,
+    spring_dwell: f32,
+    spring_tone: f32,
+    spring_mix: f32,
+    shimmer_enabled: bool,
+    shimmer_interval: ShimmerInterval,
+    shimmer_amount: f32,
+    shimmer_decay: f32,
+    shimmer_tone: f32,
+    shimmer_mix: f32,
+    convolution_enabled: bool,
+    convolution_ir: Option<ImpulseResponse>,
+    convolution_decay: f32,
+    convolution_pre_delay: f32,
+    convolution_width: f32,
+    convolution_low_cut: f32,
+    convolution_high_cut: f32,
+    convolution_mix: f32,
+    duck_enabled: bool,
+    duck_threshold: f32,
+    duck_amount: f32,
+    duck_release: f32,
+    looper_enabled: bool,
+    looper_slot: LooperSlot,
+    looper_max_length: f32,
+    looper_half_speed: bool,
+    looper_reverse: bool,
+    limiter_enabled: bool,
+    limiter_ceiling: f32,
+    limiter_release: f32,
     pub theme: String,
     show_about: bool,
 }
@@ -83,6 +261,11 @@ impl GooseDsp {
             stream: None,
             output_stream: None,
             output_level: Arc::new(Mutex::new(0.0)),
+            tuner_pitch: Arc::new(Mutex::new(None)),
+            tuner_a4: 440.0,
+            tuner_strobe: false,
+            tuner_strobe_phase: 0.0,
+            looper: Arc::new(Mutex::new(Looper::new(44100.0))),
             stream_config: Arc::new(Mutex::new(None)),
             input_volume: 0.7,
             output_volume: 0.7,
@@ -96,12 +279,175 @@ impl GooseDsp {
             eq_high: 1.0,
             gate_enabled: false,
             gate_threshold: -40.0,
+            wah_enabled: false,
+            wah_position: 0.5,
+            wah_range: WahRange::CryBaby,
+            wah_q: 5.0,
+            wah_cc: 11,
+            midi_ports: Vec::new(),
+            selected_midi_port: None,
+            midi_connection: None,
+            envelope_filter_enabled: false,
+            envelope_filter_mode: FilterMode::BandPass,
+            envelope_filter_sensitivity: 0.5,
+            envelope_filter_range: 3.0,
+            envelope_filter_q: 4.0,
+            envelope_filter_attack: 5.0,
+            envelope_filter_decay: 100.0,
+            envelope_filter_down: false,
+            envelope_filter_lfo: false,
+            envelope_filter_rate: 1.0,
+            octaver_enabled: false,
+            octaver_dry: 1.0,
+            octaver_sub: 0.5,
+            octaver_sub2: 0.0,
+            octaver_up: 0.0,
+            pitch_enabled: false,
+            pitch_mode: PitchMode::Shift,
+            pitch_semitones: 12,
+            pitch_cents: 0.0,
+            pitch_drop: 1,
+            pitch_mix: 0.5,
+            pitch_key: 0,
+            pitch_scale: Scale::Major,
+            pitch_steps: 2,
+            ring_mod_enabled: false,
+            ring_mod_shape: LfoShape::Sine,
+            ring_mod_frequency: 440.0,
+            ring_mod_fine: 0.0,
+            ring_mod_lfo_rate: 0.5,
+            ring_mod_lfo_depth: 0.0,
+            ring_mod_tracking: false,
+            ring_mod_mix: 1.0,
+            boost_enabled: false,
+            boost_gain: 6.0,
+            boost_tight: false,
+            boost_post: false,
+            bitcrusher_enabled: false,
+            bitcrusher_bits: 8.0,
+            bitcrusher_rate: 11025.0,
+            bitcrusher_anti_alias: true,
+            bitcrusher_dither: false,
+            bitcrusher_mix: 1.0,
+            volume_pedal_enabled: false,
+            volume_pedal_position: 1.0,
+            volume_pedal_taper: Taper::Audio,
+            volume_pedal_minimum: 0.0,
             cabinet_enabled: true,
+            tempo: 120.0,
+            tuner_enabled: false,
+            tuner_mute: false,
+            swell_enabled: false,
+            swell_rise: 0.6,
+            swell_sensitivity: 0.5,
+            nam_enabled: false,
+            nam_model_name: None,
+            chorus_enabled: false,
+            chorus_rate: 0.8,
+            chorus_depth: 2.0,
+            chorus_delay: 12.0,
+            chorus_mix: 0.5,
+            chorus_spread: 0.0,
+            vibrato_enabled: false,
+            vibrato_rate: 5.0,
+            vibrato_depth: 1.0,
+            univibe_enabled: false,
+            univibe_rate: 2.0,
+            univibe_depth: 0.7,
+            univibe_mode: VibeMode::Chorus,
+            flanger_enabled: false,
+            flanger_rate: 0.3,
+            flanger_depth: 0.7,
+            flanger_manual: 2.0,
+            flanger_feedback: 0.5,
+            flanger_mix: 0.5,
+            flanger_through_zero: false,
+            phaser_enabled: false,
+            phaser_stages: 4,
+            phaser_rate: 0.5,
+            phaser_depth: 0.7,
+            phaser_feedback: 0.3,
+            phaser_centre: 800.0,
+            phaser_mix: 0.5,
+            phaser_sync: false,
+            phaser_division: 2,
+            tremolo_enabled: false,
+            tremolo_shape: LfoShape::Sine,
+            tremolo_rate: 5.0,
+            tremolo_depth: 0.5,
+            tremolo_sync: false,
+            tremolo_division: 4,
+            tremolo_pan: false,
+            tremolo_harmonic: false,
+            rotary_enabled: false,
+            rotary_fast: false,
+            rotary_spread: 0.7,
+            tape_echo_enabled: false,
+            tape_echo_time: 300.0,
+            tape_echo_heads: 0,
+            tape_echo_feedback: 0.4,
+            tape_echo_saturation: 0.5,
+            tape_echo_tone: 0.5,
+            tape_echo_wow: 0.3,
+            tape_echo_flutter: 0.3,
+            tape_echo_mix: 0.4,
+            bbd_enabled: false,
+            bbd_time: 300.0,
+            bbd_feedback: 0.4,
+            bbd_mix: 0.4,
+            bbd_mod_rate: 0.5,
+            bbd_mod_depth: 0.0,
+            bbd_noise: 0.5,
+            stereo_delay_enabled: false,
+            stereo_delay_mode: DelayMode::Stereo,
+            stereo_delay_left: 375.0,
+            stereo_delay_right: 500.0,
+            stereo_delay_feedback: 0.4,
+            stereo_delay_mix: 0.4,
+            stereo_delay_sync: false,
+            stereo_delay_left_division: 3,
+            stereo_delay_right_division: 2,
+            stereo_delay_taps: 4,
+            stereo_delay_tap_times: [125.0, 250.0, 375.0, 500.0, 625.0, 750.0, 875.0, 1000.0],
+            stereo_delay_tap_divisions: [6, 4, 3, 2, 2, 1, 1, 0],
+            stereo_delay_tap_levels: [1.0, 0.8, 0.6, 0.5, 0.4, 0.3, 0.25, 0.2],
+            stereo_delay_tap_pans: [-0.8, 0.8, -0.5, 0.5, -0.3, 0.3, -0.1, 0.1],
+            spring_enabled: false,
+            spring_dwell: 0.5,
+            spring_tone: 0.5,
+            spring_mix: 0.3,
+            shimmer_enabled: false,
+            shimmer_interval: ShimmerInterval::OctaveUp,
+            shimmer_amount: 0.5,
+            shimmer_decay: 4.0,
+            shimmer_tone: 0.5,
+            shimmer_mix: 0.4,
+            convolution_enabled: false,
+            convolution_ir: None,
+            convolution_decay: 1.0,
+            convolution_pre_delay: 20.0,
+            convolution_width: 1.0,
+            convolution_low_cut: 100.0,
+            convolution_high_cut: 8000.0,
+            convolution_mix: 0.3,
+            duck_enabled: false,
+            duck_threshold: -30.0,
+            duck_amount: 0.7,
+            duck_release: 0.3,
+            looper_enabled: false,
+            looper_slot: LooperSlot::Output,
+            looper_max_length: 60.0,
+            looper_half_speed: false,
+            looper_reverse: false,
+            limiter_enabled: true,
+            limiter_ceiling: -0.3,
+            limiter_release: 100.0,
             theme: "System".to_string(),
             show_about: false,
         };
 
         goose_dsp.load_settings();
+        goose_dsp.refresh_midi_ports();
         goose_dsp.set_stream();
         goose_dsp
     }
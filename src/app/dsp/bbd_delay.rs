@@ -0,0 +1,119 @@
+use super::envelope::EnvelopeFollower;
+use super::noise::Noise;
+use super::smoothing::SmoothedValue;
+use super::svf::StateVariableFilter;
+
+// Two MN3005-style 4096-stage chips in series, as in the Memory Man
+const STAGES: usize = 8192;
+const MAX_TIME_MS: f32 = 550.0;
+// Compander reference level; signals here pass through at unity
+const COMPANDER_REF: f32 = 0.25;
+
+pub struct BbdDelay {
+    feedback: f32,
+    mix: f32,
+    mod_rate: f32,
+    mod_depth: f32,
+    noise_level: f32,
+    sample_rate: f32,
+    // Delay time in seconds, smoothed so the clock glides like a real pot sweep
+    time: SmoothedValue,
+    buckets: Vec<f32>,
+    bucket_pos: usize,
+    clock_phase: f32,
+    held: f32,
+    anti_alias: StateVariableFilter,
+    reconstruction: StateVariableFilter,
+    compressor: EnvelopeFollower,
+    expander: EnvelopeFollower,
+    noise: Noise,
+    lfo_phase: f32,
+    last_wet: f32,
+}
+
+impl BbdDelay {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            feedback: 0.4,
+            mix: 0.4,
+            mod_rate: 0.5,
+            mod_depth: 0.0,
+            noise_level: 0.5,
+            sample_rate,
+            time: SmoothedValue::new(sample_rate, 0.3, 150.0),
+            buckets: vec![0.0; STAGES],
+            bucket_pos: 0,
+            clock_phase: 0.0,
+            held: 0.0,
+            anti_alias: StateVariableFilter::default(),
+            reconstruction: StateVariableFilter::default(),
+            compressor: EnvelopeFollower::new(sample_rate, 0.005, 0.05),
+            expander: EnvelopeFollower::new(sample_rate, 0.005, 0.05),
+            noise: Noise::new(0x2545_f491),
+            lfo_phase: 0.0,
+            last_wet: 0.0,
+        }
+    }
+
+    // Time in milliseconds, feedback and mix 0..1.
+    pub fn set_params(&mut self, time: f32, feedback: f32, mix: f32) {
+        self.time.set_target(time.clamp(20.0, MAX_TIME_MS) * 0.001);
+        self.feedback = feedback.clamp(0.0, 1.05);
+        self.mix = mix;
+    }
+
+    // Modulation rate in Hz, depth and noise 0..1.
+    pub fn set_character(&mut self, mod_rate: f32, mod_depth: f32, noise: f32) {
+        self.mod_rate = mod_rate;
+        self.mod_depth = mod_depth;
+        self.noise_level = noise;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let input = (*l + *r) * 0.5 + self.feedback * self.last_wet;
+
+            let lfo = (std::f32::consts::TAU * self.lfo_phase).sin();
+            self.lfo_phase = (self.lfo_phase + self.mod_rate / self.sample_rate).fract();
+            let time = self.time.next() * (1.0 + lfo * self.mod_depth * 0.02);
+
+            // The clock has to run slower for longer delays, so the filters that
+            // keep it free of aliasing close down with it.
+            let clock = STAGES as f32 / time;
+            let cutoff = (clock * 0.5 * 0.4).min(self.sample_rate * 0.45);
+
+            // 2:1 compressor ahead of the chips
+            let envelope = self.compressor.process(input.abs()).max(1e-4);
+            let compressed = input * (COMPANDER_REF / envelope).sqrt();
+            let filtered = self
+                .anti_alias
+                .process(compressed, cutoff, 0.707, self.sample_rate)
+                .low;
+
+            // Step the bucket chain once per clock tick; each tick moves one
+            // sample in and the oldest one out.
+            self.clock_phase += clock / self.sample_rate;
+            while self.clock_phase >= 1.0 {
+                self.clock_phase -= 1.0;
+                let hiss = self.noise.next() * self.noise_level * 0.003;
+                let oldest = self.buckets[self.bucket_pos];
+                self.buckets[self.bucket_pos] = (filtered + hiss).tanh();
+                self.bucket_pos = (self.bucket_pos + 1) % STAGES;
+                self.held = oldest;
+            }
+
+            let smoothed = self
+                .reconstruction
+                .process(self.held, cutoff, 0.707, self.sample_rate)
+                .low;
+
+            // Matching 2:1 expander restores the dynamics and makes the noise breathe
+            let level = self.expander.process(smoothed.abs());
+            let wet = smoothed * level / COMPANDER_REF;
+            self.last_wet = wet.tanh();
+
+            *l += self.last_wet * self.mix;
+            *r += self.last_wet * self.mix;
+        }
+    }
+}
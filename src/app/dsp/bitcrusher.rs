@@ -0,0 +1,79 @@
+use super::noise::Noise;
+use super::svf::StateVariableFilter;
+
+pub struct Bitcrusher {
+    bits: f32,
+    target_rate: f32,
+    anti_alias: bool,
+    dither: bool,
+    mix: f32,
+    sample_rate: f32,
+    phase: f32,
+    held: f32,
+    filter: StateVariableFilter,
+    noise: Noise,
+}
+
+impl Bitcrusher {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            bits: 24.0,
+            target_rate: sample_rate,
+            anti_alias: false,
+            dither: false,
+            mix: 1.0,
+            sample_rate,
+            phase: 0.0,
+            held: 0.0,
+            filter: StateVariableFilter::default(),
+            noise: Noise::new(0x9e37_79b9),
+        }
+    }
+
+    // Bits 1..24 (fractional values allowed), target rate in Hz.
+    pub fn set_params(
+        &mut self,
+        bits: f32,
+        target_rate: f32,
+        anti_alias: bool,
+        dither: bool,
+        mix: f32,
+    ) {
+        self.bits = bits.clamp(1.0, 24.0);
+        self.target_rate = target_rate.clamp(100.0, self.sample_rate);
+        self.anti_alias = anti_alias;
+        self.dither = dither;
+        self.mix = mix;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        let levels = 2.0_f32.powf(self.bits - 1.0);
+        let step = self.target_rate / self.sample_rate;
+
+        for sample in input.iter_mut() {
+            let dry = *sample;
+            let filtered = if self.anti_alias {
+                self.filter
+                    .process(dry, self.target_rate * 0.45, 0.707, self.sample_rate)
+                    .low
+            } else {
+                dry
+            };
+
+            // Sample and hold at the reduced rate
+            self.phase += step;
+            if self.phase >= 1.0 {
+                self.phase -= 1.0;
+                let dither = if self.dither {
+                    // Triangular dither of one LSB
+                    (self.noise.next() + self.noise.next()) * 0.5 / levels
+                } else {
+                    0.0
+                };
+                self.held = ((filtered + dither) * levels).round() / levels;
+            }
+
+            *sample = dry + (self.held - dry) * self.mix;
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::smoothing::SmoothedValue;
+
+const TIGHT_HZ: f32 = 150.0;
+
+pub struct Boost {
+    tight: bool,
+    sample_rate: f32,
+    gain: SmoothedValue,
+    low: f32,
+}
+
+impl Boost {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            tight: false,
+            sample_rate,
+            gain: SmoothedValue::new(sample_rate, 1.0, 20.0),
+            low: 0.0,
+        }
+    }
+
+    // Gain in dB, tight cuts the low end before it hits the drive.
+    pub fn set_params(&mut self, gain_db: f32, tight: bool) {
+        self.gain
+            .set_target(10.0_f32.powf(gain_db.clamp(0.0, 20.0) / 20.0));
+        self.tight = tight;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        let dt = 1.0 / self.sample_rate;
+        let alpha = dt / (1.0 / (std::f32::consts::TAU * TIGHT_HZ) + dt);
+
+        for sample in input.iter_mut() {
+            self.low += alpha * (*sample - self.low);
+            let signal = if self.tight {
+                *sample - self.low
+            } else {
+                *sample
+            };
+            *sample = signal * self.gain.next();
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use super::delay_line::DelayLine;
+
+const MAX_DELAY_MS: f32 = 50.0;
+
+pub struct Chorus {
+    rate: f32,
+    depth: f32,
+    delay: f32,
+    mix: f32,
+    spread: f32,
+    sample_rate: f32,
+    phase: f32,
+    lines: [DelayLine; 2],
+}
+
+impl Chorus {
+    pub fn new(sample_rate: f32) -> Self {
+        let max_delay = (MAX_DELAY_MS * 0.001 * sample_rate) as usize;
+        Self {
+            rate: 0.8,
+            depth: 2.0,
+            delay: 12.0,
+            mix: 0.5,
+            spread: 0.0,
+            sample_rate,
+            phase: 0.0,
+            lines: [DelayLine::new(max_delay), DelayLine::new(max_delay)],
+        }
+    }
+
+    // Rate in Hz, depth and delay in milliseconds, spread 0..1 where 1 puts
+    // the right channel LFO in quadrature with the left.
+    pub fn set_params(&mut self, rate: f32, depth: f32, delay: f32, mix: f32, spread: f32) {
+        self.rate = rate;
+        self.depth = depth.min(delay);
+        self.delay = delay;
+        self.mix = mix;
+        self.spread = spread;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let ms_to_samples = 0.001 * self.sample_rate;
+        let phase_step = self.rate / self.sample_rate;
+        let offsets = [0.0, 0.25 * self.spread];
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            for ((sample, line), offset) in [l, r].into_iter().zip(&mut self.lines).zip(offsets) {
+                let lfo = (std::f32::consts::TAU * (self.phase + offset)).sin();
+                let delay = (self.delay + self.depth * lfo) * ms_to_samples;
+
+                line.write(*sample);
+                let wet = line.read(delay);
+                *sample = *sample * (1.0 - self.mix) + wet * self.mix;
+            }
+
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use super::delay_line::DelayLine;
+use rustfft::num_complex::Complex;
+use rustfft::{Fft, FftPlanner};
+use std::path::Path;
+use std::sync::Arc;
+
+// Partition length; also the latency the convolution adds to the wet path.
+const BLOCK: usize = 256;
+const FFT_SIZE: usize = BLOCK * 2;
+// Only the non-negative bins are multiplied, the rest follow by symmetry.
+const BINS: usize = FFT_SIZE / 2 + 1;
+pub const MAX_PRE_DELAY_MS: f32 = 250.0;
+
+pub struct ImpulseResponse {
+    pub name: String,
+    sample_rate: u32,
+    channels: [Vec<f32>; 2],
+}
+
+impl ImpulseResponse {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut reader = hound::WavReader::open(path)
+            .map_err(|err| format!("Failed to open impulse response: {}", err))?;
+        let spec = reader.spec();
+
+        let samples: Vec<f32> = match spec.sample_format {
+            hound::SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1_i64 << (spec.bits_per_sample - 1)) as f32;
+                reader
+                    .samples::<i32>()
+                    .map(|sample| sample.map(|value| value as f32 * scale))
+                    .collect()
+            }
+        }
+        .map_err(|err| format!("Failed to read impulse response: {}", err))?;
+
+        let channel_count = spec.channels.max(1) as usize;
+        if samples.len() < channel_count {
+            return Err("Impulse response is empty".to_string());
+        }
+
+        // Mono files feed both sides; anything past two channels is ignored.
+        let channel = |index: usize| -> Vec<f32> {
+            samples
+                .chunks(channel_count)
+                .map(|frame| frame[index.min(channel_count - 1)])
+                .collect()
+        };
+
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "Impulse response".to_string());
+
+        Ok(Self {
+            name,
+            sample_rate: spec.sample_rate,
+            channels: [channel(0), channel(1)],
+        })
+    }
+}
+
+// Pre-transformed IR partitions, built off the audio thread.
+pub struct ConvolutionKernel {
+    partitions: [Vec<Vec<Complex<f32>>>; 2],
+    forward: Arc<dyn Fft<f32>>,
+    inverse: Arc<dyn Fft<f32>>,
+}
+
+impl ConvolutionKernel {
+    // Decay 0..1 is the fraction of the IR kept; the second half of what's
+    // kept fades out so the trimmed tail doesn't stop dead.
+    pub fn new(ir: &ImpulseResponse, sample_rate: f32, decay: f32) -> Self {
+        let step = ir.sample_rate as f32 / sample_rate;
+
+        let trimmed = ir.channels.each_ref().map(|channel| {
+            let resampled = resample(channel, step);
+            let kept = ((resampled.len() as f32 * decay.clamp(0.01, 1.0)) as usize).max(1);
+            let fade_start = kept / 2;
+            resampled[..kept]
+                .iter()
+                .enumerate()
+                .map(|(i, &sample)| {
+                    if i < fade_start {
+                        sample
+                    } else {
+                        let t = (i - fade_start) as f32 / (kept - fade_start) as f32;
+                        sample * 0.5 * (1.0 + (std::f32::consts::PI * t).cos())
+                    }
+                })
+                .collect::<Vec<f32>>()
+        });
+
+        // Normalise to unit energy so loud and quiet IR files sit at a similar level
+        let energy = trimmed
+            .iter()
+            .map(|channel| channel.iter().map(|sample| sample * sample).sum::<f32>())
+            .fold(0.0, f32::max);
+        let gain = 1.0 / energy.sqrt().max(1e-6);
+
+        Self::from_taps(trimmed.map(|channel| channel.iter().map(|sample| sample * gain).collect()))
+    }
+
+    // Both channels must be the same length.
+    fn from_taps(taps: [Vec<f32>; 2]) -> Self {
+        let mut planner = FftPlanner::new();
+        let forward = planner.plan_fft_forward(FFT_SIZE);
+        let inverse = planner.plan_fft_inverse(FFT_SIZE);
+
+        let partitions = taps.map(|channel| {
+            channel
+                .chunks(BLOCK)
+                .map(|chunk| {
+                    let mut buffer = vec![Complex::default(); FFT_SIZE];
+                    for (bin, &sample) in buffer.iter_mut().zip(chunk) {
+                        bin.re = sample;
+                    }
+                    forward.process(&mut buffer);
+                    buffer.truncate(BINS);
+                    buffer
+                })
+                .collect()
+        });
+
+        Self {
+            partitions,
+            forward,
+            inverse,
+        }
+    }
+}
+
+fn resample(input: &[f32], step: f32) -> Vec<f32> {
+    if (step - 1.0).abs() < f32::EPSILON {
+        return input.to_vec();
+    }
+    let length = ((input.len() as f32 / step) as usize).max(1);
+    (0..length)
+        .map(|i| {
+            let position = i as f32 * step;
+            let index = position as usize;
+            let frac = position - index as f32;
+            let next = input.get(index + 1).copied().unwrap_or(0.0);
+            input[index] + (next - input[index]) * frac
+        })
+        .collect()
+}
+
+// Uniformly partitioned overlap-save convolution of the mono input with
+// both IR channels.
+#[derive(Clone)]
+pub struct ConvolutionReverb {
+    kernel: Arc<ConvolutionKernel>,
+    pre_delay: f32,
+    width: f32,
+    low_cut: f32,
+    high_cut: f32,
+    mix: f32,
+    sample_rate: f32,
+    pre_delay_line: DelayLine,
+    // Input spectra of past blocks, newest at `history_pos`
+    history: Vec<Vec<Complex<f32>>>,
+    history_pos: usize,
+    input: Vec<f32>,
+    previous_input: Vec<f32>,
+    output: [Vec<f32>; 2],
+    block_pos: usize,
+    scratch: Vec<Complex<f32>>,
+    low_cut_state: [f32; 2],
+    high_cut_state: [f32; 2],
+}
+
+impl ConvolutionReverb {
+    pub fn new(kernel: Arc<ConvolutionKernel>, sample_rate: f32) -> Self {
+        let partition_count = kernel.partitions[0].len().max(1);
+        Self {
+            kernel,
+            pre_delay: 0.0,
+            width: 1.0,
+            low_cut: 20.0,
+            high_cut: 20000.0,
+            mix: 0.3,
+            sample_rate,
+            pre_delay_line: DelayLine::new((MAX_PRE_DELAY_MS * 0.001 * sample_rate) as usize),
+            history: vec![vec![Complex::default(); BINS]; partition_count],
+            history_pos: 0,
+            input: vec![0.0; BLOCK],
+            previous_input: vec![0.0; BLOCK],
+            output: [vec![0.0; BLOCK], vec![0.0; BLOCK]],
+            block_pos: 0,
+            scratch: vec![Complex::default(); FFT_SIZE],
+            low_cut_state: [0.0; 2],
+            high_cut_state: [0.0; 2],
+        }
+    }
+
+    pub fn uses(&self, kernel: &Arc<ConvolutionKernel>) -> bool {
+        Arc::ptr_eq(&self.kernel, kernel)
+    }
+
+    // Takes over the running state of the reverb this one replaces, so the
+    // tail rings on through the new IR instead of cutting out. Buffers are
+    // swapped rather than reallocated; `previous` is left with the fresh ones.
+    pub fn carry_over(&mut self, previous: &mut ConvolutionReverb) {
+        std::mem::swap(&mut self.pre_delay_line, &mut previous.pre_delay_line);
+        std::mem::swap(&mut self.input, &mut previous.input);
+        std::mem::swap(&mut self.previous_input, &mut previous.previous_input);
+        std::mem::swap(&mut self.output, &mut previous.output);
+        self.block_pos = previous.block_pos;
+        self.low_cut_state = previous.low_cut_state;
+        self.high_cut_state = previous.high_cut_state;
+
+        let previous_count = previous.history.len();
+        for (age, spectrum) in self.history.iter_mut().take(previous_count).enumerate() {
+            spectrum
+                .copy_from_slice(&previous.history[(previous.history_pos + age) % previous_count]);
+        }
+        self.history_pos = 0;
+    }
+
+    // Pre-delay in milliseconds, width 0 (mono) to 2, cuts in Hz.
+    pub fn set_params(
+        &mut self,
+        pre_delay: f32,
+        width: f32,
+        low_cut: f32,
+        high_cut: f32,
+        mix: f32,
+    ) {
+        self.pre_delay = pre_delay.clamp(0.0, MAX_PRE_DELAY_MS) * 0.001 * self.sample_rate;
+        self.width = width;
+        self.low_cut = low_cut;
+        self.high_cut = high_cut.min(self.sample_rate * 0.45);
+        self.mix = mix;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let low_alpha = 1.0 - (-std::f32::consts::TAU * self.low_cut / self.sample_rate).exp();
+        let high_alpha = 1.0 - (-std::f32::consts::TAU * self.high_cut / self.sample_rate).exp();
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            self.pre_delay_line.write((*l + *r) * 0.5);
+            self.input[self.block_pos] = self.pre_delay_line.read(self.pre_delay);
+
+            let mut wet = [
+                self.output[0][self.block_pos],
+                self.output[1][self.block_pos],
+            ];
+            for (side, sample) in wet.iter_mut().enumerate() {
+                self.low_cut_state[side] += low_alpha * (*sample - self.low_cut_state[side]);
+                self.high_cut_state[side] +=
+                    high_alpha * (*sample - self.low_cut_state[side] - self.high_cut_state[side]);
+                *sample = self.high_cut_state[side];
+            }
+
+            let mid = (wet[0] + wet[1]) * 0.5;
+            let side = (wet[0] - wet[1]) * 0.5 * self.width;
+            *l += (mid + side) * self.mix;
+            *r += (mid - side) * self.mix;
+
+            self.block_pos += 1;
+            if self.block_pos == BLOCK {
+                self.block_pos = 0;
+                self.process_block();
+            }
+        }
+    }
+
+    fn process_block(&mut self) {
+        // Overlap-save: transform the previous and current blocks together
+        for (bin, &sample) in self
+            .scratch
+            .iter_mut()
+            .zip(self.previous_input.iter().chain(&self.input))
+        {
+            *bin = Complex::new(sample, 0.0);
+        }
+        self.kernel.forward.process(&mut self.scratch);
+        std::mem::swap(&mut self.previous_input, &mut self.input);
+
+        let partition_count = self.history.len();
+        self.history_pos = (self.history_pos + partition_count - 1) % partition_count;
+        self.history[self.history_pos].copy_from_slice(&self.scratch[..BINS]);
+
+        for (side, partitions) in self.kernel.partitions.iter().enumerate() {
+            let mut sum = [Complex::default(); BINS];
+            for (age, partition) in partitions.iter().enumerate() {
+                let spectrum = &self.history[(self.history_pos + age) % partition_count];
+                for ((total, &x), &h) in sum.iter_mut().zip(spectrum).zip(partition) {
+                    *total += x * h;
+                }
+            }
+
+            // Rebuild the mirrored half of the spectrum of a real signal
+            self.scratch[..BINS].copy_from_slice(&sum);
+            for bin in BINS..FFT_SIZE {
+                self.scratch[bin] = sum[FFT_SIZE - bin].conj();
+            }
+            self.kernel.inverse.process(&mut self.scratch);
+
+            let scale = 1.0 / FFT_SIZE as f32;
+            for (out, bin) in self.output[side].iter_mut().zip(&self.scratch[BLOCK..]) {
+                *out = bin.re * scale;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn direct_convolution(input: &[f32], taps: &[f32]) -> Vec<f32> {
+        (0..input.len())
+            .map(|n| {
+                taps.iter()
+                    .enumerate()
+                    .take(n + 1)
+                    .map(|(k, &tap)| tap * input[n - k])
+                    .sum()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn process_block_matches_direct_convolution() {
+        // Three partitions, the last one partly filled
+        let taps: [Vec<f32>; 2] = [
+            (0..600)
+                .map(|i| (i as f32 * 0.37).sin() * 0.99_f32.powi(i))
+                .collect(),
+            (0..600)
+                .map(|i| (i as f32 * 0.11).cos() * 0.98_f32.powi(i))
+                .collect(),
+        ];
+        let input: Vec<f32> = (0..BLOCK * 6)
+            .map(|i| (i as f32 * 0.05).sin() + (i as f32 * 0.71).cos() * 0.3)
+            .collect();
+
+        let mut reverb = ConvolutionReverb::new(
+            Arc::new(ConvolutionKernel::from_taps(taps.clone())),
+            48000.0,
+        );
+        let mut output = [Vec::new(), Vec::new()];
+        for block in input.chunks(BLOCK) {
+            reverb.input.copy_from_slice(block);
+            reverb.process_block();
+            for (side, out) in output.iter_mut().enumerate() {
+                out.extend_from_slice(&reverb.output[side]);
+            }
+        }
+
+        for (side, channel_taps) in taps.iter().enumerate() {
+            let expected = direct_convolution(&input, channel_taps);
+            for (n, (&actual, &expected)) in output[side].iter().zip(&expected).enumerate() {
+                assert!(
+                    (actual - expected).abs() < 1e-3,
+                    "side {side}, sample {n}: {actual} != {expected}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn carry_over_continues_the_tail() {
+        let taps: [Vec<f32>; 2] = [
+            (0..700).map(|i| 0.97_f32.powi(i)).collect(),
+            (0..700).map(|i| -(0.96_f32.powi(i))).collect(),
+        ];
+        let input: Vec<f32> = (0..BLOCK * 8 + 37)
+            .map(|i| (i as f32 * 0.13).sin())
+            .collect();
+        let kernel = || Arc::new(ConvolutionKernel::from_taps(taps.clone()));
+        let run = |reverb: &mut ConvolutionReverb, samples: &[f32]| {
+            let mut left = samples.to_vec();
+            let mut right = samples.to_vec();
+            reverb.set_params(10.0, 1.0, 20.0, 20000.0, 1.0);
+            reverb.process(&mut left, &mut right);
+            left.into_iter().chain(right).collect::<Vec<f32>>()
+        };
+
+        // Switch part way through a block to a reverb with the same IR
+        let split = BLOCK * 3 + 100;
+        let mut whole = ConvolutionReverb::new(kernel(), 48000.0);
+        run(&mut whole, &input[..split]);
+        let expected = run(&mut whole, &input[split..]);
+
+        let mut first = ConvolutionReverb::new(kernel(), 48000.0);
+        run(&mut first, &input[..split]);
+        let mut second = ConvolutionReverb::new(kernel(), 48000.0);
+        second.carry_over(&mut first);
+        let actual = run(&mut second, &input[split..]);
+
+        for (n, (a, e)) in actual.iter().zip(&expected).enumerate() {
+            assert!((a - e).abs() < 1e-4, "sample {n}: {a} != {e}");
+        }
+    }
+}
@@ -0,0 +1,31 @@
+#[derive(Clone)]
+pub struct DelayLine {
+    buffer: Vec<f32>,
+    write_pos: usize,
+}
+
+impl DelayLine {
+    pub fn new(max_delay: usize) -> Self {
+        Self {
+            buffer: vec![0.0; max_delay.max(1) + 2],
+            write_pos: 0,
+        }
+    }
+
+    pub fn write(&mut self, sample: f32) {
+        self.buffer[self.write_pos] = sample;
+        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+    }
+
+    // Reads `delay` samples behind the most recent write, linearly interpolated.
+    pub fn read(&self, delay: f32) -> f32 {
+        let len = self.buffer.len();
+        let delay = delay.clamp(0.0, (len - 2) as f32);
+        let whole = delay.floor();
+        let frac = delay - whole;
+
+        let newer = (self.write_pos + len - 1 - whole as usize) % len;
+        let older = (newer + len - 1) % len;
+        self.buffer[newer] + (self.buffer[older] - self.buffer[newer]) * frac
+    }
+}
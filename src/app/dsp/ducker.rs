@@ -0,0 +1,41 @@
+use super::envelope::EnvelopeFollower;
+
+// How far above the threshold the input has to be before the full amount applies
+const KNEE_DB: f32 = 6.0;
+
+pub struct Ducker {
+    threshold: f32,
+    amount: f32,
+    follower: EnvelopeFollower,
+}
+
+impl Ducker {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            threshold: -30.0,
+            amount: 0.7,
+            follower: EnvelopeFollower::new(sample_rate, 0.005, 0.3),
+        }
+    }
+
+    // Threshold in dB, amount 0..1 of wet attenuation, release in seconds.
+    pub fn set_params(&mut self, threshold: f32, amount: f32, release: f32) {
+        self.threshold = threshold;
+        self.amount = amount.clamp(0.0, 1.0);
+        self.follower.set_times(0.005, release.max(0.01));
+    }
+
+    // Scales the wet part (output minus dry) by a gain that drops while the dry signal is loud.
+    pub fn process(&mut self, dry: (&[f32], &[f32]), left: &mut [f32], right: &mut [f32]) {
+        for (((l, r), dry_l), dry_r) in left.iter_mut().zip(right.iter_mut()).zip(dry.0).zip(dry.1)
+        {
+            let envelope = self.follower.process((dry_l + dry_r).abs() * 0.5);
+            let level_db = 20.0 * envelope.max(1e-6).log10();
+            let over = ((level_db - self.threshold) / KNEE_DB).clamp(0.0, 1.0);
+            let gain = 1.0 - self.amount * over;
+
+            *l = dry_l + (*l - dry_l) * gain;
+            *r = dry_r + (*r - dry_r) * gain;
+        }
+    }
+}
@@ -0,0 +1,35 @@
+pub struct EnvelopeFollower {
+    attack_coef: f32,
+    release_coef: f32,
+    sample_rate: f32,
+    envelope: f32,
+}
+
+impl EnvelopeFollower {
+    // Attack and release times in seconds.
+    pub fn new(sample_rate: f32, attack_time: f32, release_time: f32) -> Self {
+        let mut follower = Self {
+            attack_coef: 0.0,
+            release_coef: 0.0,
+            sample_rate,
+            envelope: 0.0,
+        };
+        follower.set_times(attack_time, release_time);
+        follower
+    }
+
+    pub fn set_times(&mut self, attack_time: f32, release_time: f32) {
+        self.attack_coef = (-1.0 / (attack_time * self.sample_rate)).exp();
+        self.release_coef = (-1.0 / (release_time * self.sample_rate)).exp();
+    }
+
+    pub fn process(&mut self, level: f32) -> f32 {
+        let coef = if level > self.envelope {
+            self.attack_coef
+        } else {
+            self.release_coef
+        };
+        self.envelope = level * (1.0 - coef) + self.envelope * coef;
+        self.envelope
+    }
+}
@@ -0,0 +1,89 @@
+use super::envelope::EnvelopeFollower;
+use super::svf::StateVariableFilter;
+
+const BASE_FREQ: f32 = 200.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    BandPass,
+    LowPass,
+}
+
+pub struct EnvelopeFilter {
+    mode: FilterMode,
+    sensitivity: f32,
+    range: f32,
+    q: f32,
+    sweep_down: bool,
+    lfo_rate: Option<f32>,
+    sample_rate: f32,
+    phase: f32,
+    follower: EnvelopeFollower,
+    filter: StateVariableFilter,
+}
+
+impl EnvelopeFilter {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            mode: FilterMode::BandPass,
+            sensitivity: 0.5,
+            range: 3.0,
+            q: 4.0,
+            sweep_down: false,
+            lfo_rate: None,
+            sample_rate,
+            phase: 0.0,
+            follower: EnvelopeFollower::new(sample_rate, 0.005, 0.1),
+            filter: StateVariableFilter::default(),
+        }
+    }
+
+    // Sensitivity 0..1, range in octaves above the base frequency.
+    pub fn set_params(&mut self, mode: FilterMode, sensitivity: f32, range: f32, q: f32) {
+        self.mode = mode;
+        self.sensitivity = sensitivity;
+        self.range = range;
+        self.q = q;
+    }
+
+    // Attack and decay in milliseconds. Sweeping down starts the filter open
+    // and closes it as the envelope rises.
+    pub fn set_envelope(&mut self, attack: f32, decay: f32, sweep_down: bool) {
+        self.follower.set_times(attack * 0.001, decay * 0.001);
+        self.sweep_down = sweep_down;
+    }
+
+    // Drives the sweep from an LFO at the given rate instead of the envelope.
+    pub fn set_lfo(&mut self, rate: Option<f32>) {
+        self.lfo_rate = rate;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        // Up to 40 dB of envelope gain at full sensitivity.
+        let envelope_gain = 10.0_f32.powf(2.0 * self.sensitivity);
+
+        for sample in input.iter_mut() {
+            let control = match self.lfo_rate {
+                Some(rate) => {
+                    self.phase = (self.phase + rate / self.sample_rate).fract();
+                    0.5 * (1.0 - (std::f32::consts::TAU * self.phase).cos())
+                }
+                None => (self.follower.process(sample.abs()) * envelope_gain).min(1.0),
+            };
+            let control = if self.sweep_down {
+                1.0 - control
+            } else {
+                control
+            };
+
+            let cutoff = BASE_FREQ * 2.0_f32.powf(self.range * control);
+            let output = self
+                .filter
+                .process(*sample, cutoff, self.q, self.sample_rate);
+            *sample = match self.mode {
+                FilterMode::BandPass => output.band,
+                FilterMode::LowPass => output.low,
+            };
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use super::delay_line::DelayLine;
+
+const MAX_DELAY_MS: f32 = 25.0;
+const SWEEP_MS: f32 = 5.0;
+
+pub struct Flanger {
+    rate: f32,
+    depth: f32,
+    manual: f32,
+    feedback: f32,
+    mix: f32,
+    through_zero: bool,
+    sample_rate: f32,
+    phase: f32,
+    lines: [DelayLine; 2],
+    last_wet: [f32; 2],
+}
+
+impl Flanger {
+    pub fn new(sample_rate: f32) -> Self {
+        let max_delay = (MAX_DELAY_MS * 0.001 * sample_rate) as usize;
+        Self {
+            rate: 0.3,
+            depth: 0.7,
+            manual: 2.0,
+            feedback: 0.5,
+            mix: 0.5,
+            through_zero: false,
+            sample_rate,
+            phase: 0.0,
+            lines: [DelayLine::new(max_delay), DelayLine::new(max_delay)],
+            last_wet: [0.0; 2],
+        }
+    }
+
+    // Rate in Hz, depth 0..1, manual delay in milliseconds, feedback -1..1.
+    pub fn set_params(
+        &mut self,
+        rate: f32,
+        depth: f32,
+        manual: f32,
+        feedback: f32,
+        mix: f32,
+        through_zero: bool,
+    ) {
+        self.rate = rate;
+        self.depth = depth;
+        self.manual = manual;
+        self.feedback = feedback.clamp(-0.95, 0.95);
+        self.mix = mix;
+        self.through_zero = through_zero;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let ms_to_samples = 0.001 * self.sample_rate;
+        let phase_step = self.rate / self.sample_rate;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let lfo = (std::f32::consts::TAU * self.phase).sin();
+
+            // Through-zero mode sweeps the wet tap across a dry path that is
+            // itself delayed by the manual time, so the two cancel at the crossing.
+            let (wet_delay, dry_delay) = if self.through_zero {
+                (self.manual * (1.0 + self.depth * lfo), Some(self.manual))
+            } else {
+                (
+                    self.manual + self.depth * SWEEP_MS * 0.5 * (1.0 + lfo),
+                    None,
+                )
+            };
+
+            for ((sample, line), last_wet) in [l, r]
+                .into_iter()
+                .zip(&mut self.lines)
+                .zip(&mut self.last_wet)
+            {
+                line.write(*sample + self.feedback * *last_wet);
+                let wet = line.read(wet_delay * ms_to_samples);
+                let dry = dry_delay.map_or(*sample, |delay| line.read(delay * ms_to_samples));
+                *last_wet = wet;
+                *sample = dry * (1.0 - self.mix) + wet * self.mix;
+            }
+
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
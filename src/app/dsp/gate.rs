@@ -1,19 +1,15 @@
+use super::envelope::EnvelopeFollower;
+
 pub struct NoiseGate {
     threshold: f32,
-    attack_time: f32,
-    release_time: f32,
-    sample_rate: f32,
-    envelope: f32,
+    follower: EnvelopeFollower,
 }
 
 impl NoiseGate {
     pub fn new(sample_rate: f32) -> Self {
         Self {
             threshold: -60.0,
-            attack_time: 0.1,
-            release_time: 0.2,
-            sample_rate,
-            envelope: 0.0,
+            follower: EnvelopeFollower::new(sample_rate, 0.1, 0.2),
         }
     }
 
@@ -22,9 +18,6 @@ impl NoiseGate {
     }
 
     pub fn process(&mut self, input: &mut [f32]) {
-        let attack_coef = (-1.0 / (self.attack_time * self.sample_rate)).exp();
-        let release_coef = (-1.0 / (self.release_time * self.sample_rate)).exp();
-
         for sample in input.iter_mut() {
             // Convert to dB
             let input_level = if *sample != 0.0 {
@@ -34,18 +27,10 @@ impl NoiseGate {
             };
 
             // Envelope follower
-            if input_level > self.envelope {
-                self.envelope = input_level * (1.0 - attack_coef) + self.envelope * attack_coef;
-            } else {
-                self.envelope = input_level * (1.0 - release_coef) + self.envelope * release_coef;
-            }
+            let envelope = self.follower.process(input_level);
 
             // Apply gate
-            let gain = if self.envelope < self.threshold {
-                0.0
-            } else {
-                1.0
-            };
+            let gain = if envelope < self.threshold { 0.0 } else { 1.0 };
 
             *sample *= gain;
         }
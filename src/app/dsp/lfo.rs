@@ -0,0 +1,33 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum LfoShape {
+    Sine,
+    Triangle,
+    Square,
+}
+
+impl LfoShape {
+    pub const ALL: [LfoShape; 3] = [LfoShape::Sine, LfoShape::Triangle, LfoShape::Square];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LfoShape::Sine => "Sine",
+            LfoShape::Triangle => "Triangle",
+            LfoShape::Square => "Square",
+        }
+    }
+
+    // Phase in cycles (0..1), output in -1..1.
+    pub fn value(self, phase: f32) -> f32 {
+        match self {
+            LfoShape::Sine => (std::f32::consts::TAU * phase).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * ((phase + 0.25).fract() - 0.5).abs(),
+            LfoShape::Square => {
+                if phase.fract() < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+        }
+    }
+}
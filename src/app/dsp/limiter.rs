@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+const LOOKAHEAD_MS: f32 = 5.0;
+
+// Stereo-linked look-ahead brickwall limiter. The required gain is held at its
+// minimum over the look-ahead window and then box-averaged over the same length,
+// so gain has fully come down by the time a peak leaves the delay.
+pub struct Limiter {
+    ceiling: f32,
+    release_coef: f32,
+    sample_rate: f32,
+    lookahead: usize,
+    delay: [VecDeque<f32>; 2],
+    // Monotonic queue of (sample index, gain) for the sliding minimum
+    minimum: VecDeque<(usize, f32)>,
+    average: VecDeque<f32>,
+    average_sum: f32,
+    held: f32,
+    index: usize,
+}
+
+impl Limiter {
+    pub fn new(sample_rate: f32) -> Self {
+        let lookahead = ((LOOKAHEAD_MS * 0.001 * sample_rate) as usize).max(1);
+        let mut limiter = Self {
+            ceiling: 1.0,
+            release_coef: 0.0,
+            sample_rate,
+            lookahead,
+            delay: [
+                VecDeque::from(vec![0.0; lookahead]),
+                VecDeque::from(vec![0.0; lookahead]),
+            ],
+            minimum: VecDeque::with_capacity(lookahead + 1),
+            average: VecDeque::from(vec![1.0; lookahead]),
+            average_sum: lookahead as f32,
+            held: 1.0,
+            index: 0,
+        };
+        limiter.set_params(-0.3, 100.0);
+        limiter
+    }
+
+    // Ceiling in dBFS, release in milliseconds.
+    pub fn set_params(&mut self, ceiling: f32, release: f32) {
+        self.ceiling = 10.0_f32.powf(ceiling.min(0.0) / 20.0);
+        self.release_coef = (-1.0 / (release.max(1.0) * 0.001 * self.sample_rate)).exp();
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let peak = l.abs().max(r.abs());
+            let target = if peak > self.ceiling {
+                self.ceiling / peak
+            } else {
+                1.0
+            };
+
+            while self.minimum.back().is_some_and(|&(_, gain)| gain >= target) {
+                self.minimum.pop_back();
+            }
+            self.minimum.push_back((self.index, target));
+            while self
+                .minimum
+                .front()
+                .is_some_and(|&(index, _)| index + self.lookahead <= self.index)
+            {
+                self.minimum.pop_front();
+            }
+            let window_min = self.minimum.front().map_or(1.0, |&(_, gain)| gain);
+            self.index += 1;
+
+            // Attack is handled by the hold and average; only recovery is smoothed here
+            self.held = if window_min < self.held {
+                window_min
+            } else {
+                window_min + (self.held - window_min) * self.release_coef
+            };
+
+            self.average.push_back(self.held);
+            self.average_sum += self.held - self.average.pop_front().unwrap_or(1.0);
+            let gain = self.average_sum / self.lookahead as f32;
+
+            for (sample, delay) in [l, r].into_iter().zip(&mut self.delay) {
+                delay.push_back(*sample);
+                let delayed = delay.pop_front().unwrap_or(0.0);
+                // Guards against rounding in the running sum
+                *sample = (delayed * gain).clamp(-self.ceiling, self.ceiling);
+            }
+        }
+    }
+}
@@ -0,0 +1,318 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LooperSlot {
+    Input,
+    PreAmp,
+    PostAmp,
+    Output,
+}
+
+impl LooperSlot {
+    pub const ALL: [LooperSlot; 4] = [
+        LooperSlot::Input,
+        LooperSlot::PreAmp,
+        LooperSlot::PostAmp,
+        LooperSlot::Output,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LooperSlot::Input => "Start of chain",
+            LooperSlot::PreAmp => "Before amp",
+            LooperSlot::PostAmp => "After amp",
+            LooperSlot::Output => "End of chain",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LooperState {
+    Empty,
+    Recording,
+    Playing,
+    Overdubbing,
+    Stopped,
+}
+
+impl LooperState {
+    pub fn name(self) -> &'static str {
+        match self {
+            LooperState::Empty => "Empty",
+            LooperState::Recording => "Recording",
+            LooperState::Playing => "Playing",
+            LooperState::Overdubbing => "Overdubbing",
+            LooperState::Stopped => "Stopped",
+        }
+    }
+}
+
+pub type SharedLooper = Arc<Mutex<Looper>>;
+
+// The transport is driven from the UI thread through the shared handle, while
+// the audio thread calls `process` from whichever slot the looper sits in.
+pub struct Looper {
+    state: LooperState,
+    sample_rate: f32,
+    max_length: usize,
+    half_speed: bool,
+    reverse: bool,
+    base: Vec<[f32; 2]>,
+    layers: Vec<Vec<[f32; 2]>>,
+    redo: Vec<Vec<[f32; 2]>>,
+    position: f32,
+}
+
+impl Looper {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            state: LooperState::Empty,
+            sample_rate,
+            max_length: (60.0 * sample_rate) as usize,
+            half_speed: false,
+            reverse: false,
+            base: Vec::new(),
+            layers: Vec::new(),
+            redo: Vec::new(),
+            position: 0.0,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.clear();
+        }
+    }
+
+    // Maximum loop length in seconds.
+    pub fn set_params(&mut self, max_length: f32, half_speed: bool, reverse: bool) {
+        self.max_length = (max_length * self.sample_rate) as usize;
+        self.half_speed = half_speed;
+        self.reverse = reverse;
+    }
+
+    pub fn state(&self) -> LooperState {
+        self.state
+    }
+
+    pub fn length_secs(&self) -> f32 {
+        self.base.len() as f32 / self.sample_rate
+    }
+
+    pub fn position_secs(&self) -> f32 {
+        match self.state {
+            LooperState::Recording => self.length_secs(),
+            _ => self.position / self.sample_rate,
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.layers.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // `buffer` comes from `spare_buffer` so nothing is allocated while the
+    // lock is held; it becomes the new loop or dub layer, if this press
+    // starts one.
+    pub fn record(&mut self, buffer: &mut Vec<[f32; 2]>) {
+        match self.state {
+            LooperState::Empty => {
+                self.base = std::mem::take(buffer);
+                self.base.clear();
+                self.state = LooperState::Recording;
+            }
+            LooperState::Recording => self.close_loop(LooperState::Playing),
+            LooperState::Playing | LooperState::Stopped => self.overdub(buffer),
+            LooperState::Overdubbing => self.state = LooperState::Playing,
+        }
+    }
+
+    pub fn play(&mut self) {
+        match self.state {
+            LooperState::Recording => self.close_loop(LooperState::Playing),
+            LooperState::Overdubbing | LooperState::Stopped => self.state = LooperState::Playing,
+            _ => {}
+        }
+    }
+
+    pub fn overdub(&mut self, layer: &mut Vec<[f32; 2]>) {
+        match self.state {
+            LooperState::Recording => {
+                self.close_loop(LooperState::Playing);
+                self.overdub(layer);
+            }
+            // A layer sized for a loop that has since changed is left with the
+            // caller rather than resized here
+            LooperState::Playing | LooperState::Stopped if layer.len() >= self.base.len() => {
+                layer.truncate(self.base.len());
+                self.layers.push(std::mem::take(layer));
+                self.redo.clear();
+                self.state = LooperState::Overdubbing;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn stop(&mut self) {
+        match self.state {
+            LooperState::Recording => self.close_loop(LooperState::Stopped),
+            LooperState::Playing | LooperState::Overdubbing => {
+                self.state = LooperState::Stopped;
+                self.position = 0.0;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn undo(&mut self) {
+        if self.state == LooperState::Overdubbing {
+            self.state = LooperState::Playing;
+        }
+        if let Some(layer) = self.layers.pop() {
+            self.redo.push(layer);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(layer) = self.redo.pop() {
+            self.layers.push(layer);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.state = LooperState::Empty;
+        self.base = Vec::new();
+        self.layers.clear();
+        self.redo.clear();
+        self.position = 0.0;
+    }
+
+    fn close_loop(&mut self, next: LooperState) {
+        if self.base.is_empty() {
+            self.state = LooperState::Empty;
+        } else {
+            self.state = next;
+            self.position = 0.0;
+        }
+    }
+
+    // Length and capacity of the buffer the next Rec/Dub press will take.
+    fn spare_buffer_len(&self) -> (usize, usize) {
+        match self.state {
+            LooperState::Empty => (0, self.max_length),
+            LooperState::Playing | LooperState::Stopped => (self.base.len(), self.base.len()),
+            LooperState::Recording | LooperState::Overdubbing => (0, 0),
+        }
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let step: f32 = match (self.half_speed, self.reverse) {
+            (false, false) => 1.0,
+            (true, false) => 0.5,
+            (false, true) => -1.0,
+            (true, true) => -0.5,
+        };
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            match self.state {
+                LooperState::Recording => {
+                    self.base.push([*l, *r]);
+                    // Closing at capacity keeps `push` from reallocating here
+                    // if the maximum length was raised mid-recording
+                    if self.base.len() >= self.max_length || self.base.len() == self.base.capacity()
+                    {
+                        self.close_loop(LooperState::Playing);
+                    }
+                }
+                LooperState::Playing | LooperState::Overdubbing => {
+                    let len = self.base.len();
+                    let index = (self.position as usize).min(len - 1);
+
+                    let mut playback = self.base[index];
+                    for layer in &self.layers {
+                        playback[0] += layer[index][0];
+                        playback[1] += layer[index][1];
+                    }
+
+                    if self.state == LooperState::Overdubbing
+                        && let Some(layer) = self.layers.last_mut()
+                    {
+                        // At half speed each slot is visited twice
+                        layer[index][0] += *l * step.abs();
+                        layer[index][1] += *r * step.abs();
+                    }
+
+                    *l += playback[0];
+                    *r += playback[1];
+                    self.position = (self.position + step).rem_euclid(len as f32);
+                }
+                LooperState::Empty | LooperState::Stopped => {}
+            }
+        }
+    }
+}
+
+// Allocates whatever the next Rec/Dub press needs with the lock released, so
+// the audio thread never waits behind a zero-fill of the whole loop.
+pub fn spare_buffer(looper: &SharedLooper) -> Vec<[f32; 2]> {
+    let (len, capacity) = looper.lock().unwrap().spare_buffer_len();
+    let mut buffer = vec![[0.0; 2]; len];
+    buffer.reserve_exact(capacity - len);
+    buffer
+}
+
+const MIXDOWN_CHUNK: usize = 8192;
+
+// The finished loop with all active layers mixed down, copied out a chunk at
+// a time so the audio thread is never held off for longer than one chunk.
+// Returns `None` if the loop is cleared or re-recorded part way through.
+pub fn mixdown(looper: &SharedLooper) -> Option<(Vec<[f32; 2]>, f32)> {
+    let (len, sample_rate) = {
+        let looper = looper.lock().unwrap();
+        (looper.base.len(), looper.sample_rate)
+    };
+    let mut frames = Vec::with_capacity(len);
+    while frames.len() < len {
+        let looper = looper.lock().unwrap();
+        if looper.base.len() != len {
+            return None;
+        }
+        let start = frames.len();
+        let end = (start + MIXDOWN_CHUNK).min(len);
+        frames.extend((start..end).map(|index| {
+            let mut frame = looper.base[index];
+            for layer in &looper.layers {
+                frame[0] += layer[index][0];
+                frame[1] += layer[index][1];
+            }
+            frame
+        }));
+    }
+    Some((frames, sample_rate))
+}
+
+pub fn export_wav(path: &Path, frames: &[[f32; 2]], sample_rate: f32) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: sample_rate as u32,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    for frame in frames {
+        for &sample in frame {
+            writer
+                .write_sample(sample)
+                .map_err(|e| format!("Failed to write loop: {}", e))?;
+        }
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to write loop: {}", e))
+}
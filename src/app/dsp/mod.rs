@@ -1,25 +1,182 @@
+pub mod bbd_delay;
+pub mod bitcrusher;
+pub mod boost;
 pub mod cabinet;
+pub mod chorus;
+pub mod convolution;
+pub mod delay_line;
+pub mod ducker;
+pub mod envelope;
+pub mod envelope_filter;
 pub mod eq;
+pub mod flanger;
 pub mod gate;
+pub mod lfo;
+pub mod limiter;
+pub mod looper;
+pub mod nam;
+pub mod noise;
+pub mod octaver;
 pub mod overdrive;
 pub mod params;
+pub mod phaser;
+pub mod pitch_detector;
+pub mod pitch_shift;
+pub mod ring_mod;
+pub mod rotary;
+pub mod safety;
+pub mod shimmer_reverb;
+pub mod smoothing;
+pub mod spring_reverb;
+pub mod stereo_delay;
+pub mod svf;
+pub mod swell;
+pub mod tape_echo;
+pub mod tempo;
+pub mod tremolo;
+pub mod univibe;
+pub mod vibrato;
+pub mod volume_pedal;
+pub mod wah;
 use cpal::StreamConfig;
 
+use self::bbd_delay::BbdDelay;
+use self::bitcrusher::Bitcrusher;
+use self::boost::Boost;
 use self::cabinet::CabinetSim;
+use self::chorus::Chorus;
+use self::convolution::ConvolutionReverb;
+use self::ducker::Ducker;
+use self::envelope_filter::EnvelopeFilter;
 use self::eq::EQ;
+use self::flanger::Flanger;
 use self::gate::NoiseGate;
+use self::limiter::Limiter;
+use self::looper::{LooperSlot, LooperState, SharedLooper};
+use self::nam::NamProcessor;
+use self::octaver::Octaver;
+use self::phaser::Phaser;
+use self::pitch_shift::PitchShifter;
+use self::ring_mod::RingModulator;
+use self::rotary::Rotary;
+use self::safety::SafetyMute;
+use self::shimmer_reverb::ShimmerReverb;
+use self::spring_reverb::SpringReverb;
+use self::stereo_delay::StereoDelay;
+use self::swell::Swell;
+use self::tape_echo::TapeEcho;
+use self::tremolo::Tremolo;
+use self::univibe::UniVibe;
+use self::vibrato::Vibrato;
+use self::volume_pedal::VolumePedal;
+use self::wah::Wah;
+
+pub struct Processors {
+    swell: Swell,
+    wah: Wah,
+    envelope_filter: EnvelopeFilter,
+    octaver: Octaver,
+    pitch_shifter: PitchShifter,
+    ring_mod: RingModulator,
+    boost: Boost,
+    nam: Option<NamProcessor>,
+    bitcrusher: Bitcrusher,
+    volume_pedal: VolumePedal,
+    chorus: Chorus,
+    vibrato: Vibrato,
+    univibe: UniVibe,
+    flanger: Flanger,
+    phaser: Phaser,
+    tremolo: Tremolo,
+    rotary: Rotary,
+    tape_echo: TapeEcho,
+    bbd_delay: BbdDelay,
+    stereo_delay: StereoDelay,
+    spring_reverb: SpringReverb,
+    shimmer_reverb: ShimmerReverb,
+    convolution: Option<ConvolutionReverb>,
+    ducker: Ducker,
+    looper: SharedLooper,
+    // Whether the looper was playing back as of its last run
+    looper_playing: bool,
+    safety: SafetyMute,
+    limiter: Limiter,
+}
+
+impl Processors {
+    pub fn new(sample_rate: f32, looper: SharedLooper) -> Self {
+        Self {
+            swell: Swell::new(sample_rate),
+            wah: Wah::new(sample_rate),
+            envelope_filter: EnvelopeFilter::new(sample_rate),
+            octaver: Octaver::new(sample_rate),
+            pitch_shifter: PitchShifter::new(sample_rate),
+            ring_mod: RingModulator::new(sample_rate),
+            boost: Boost::new(sample_rate),
+            nam: None,
+            bitcrusher: Bitcrusher::new(sample_rate),
+            volume_pedal: VolumePedal::new(sample_rate),
+            chorus: Chorus::new(sample_rate),
+            vibrato: Vibrato::new(sample_rate),
+            univibe: UniVibe::new(sample_rate),
+            flanger: Flanger::new(sample_rate),
+            phaser: Phaser::new(sample_rate),
+            tremolo: Tremolo::new(sample_rate),
+            rotary: Rotary::new(sample_rate),
+            tape_echo: TapeEcho::new(sample_rate),
+            bbd_delay: BbdDelay::new(sample_rate),
+            stereo_delay: StereoDelay::new(sample_rate),
+            spring_reverb: SpringReverb::new(sample_rate),
+            shimmer_reverb: ShimmerReverb::new(sample_rate),
+            convolution: None,
+            ducker: Ducker::new(sample_rate),
+            looper,
+            looper_playing: false,
+            safety: SafetyMute::new(sample_rate),
+            limiter: Limiter::new(sample_rate),
+        }
+    }
+
+    fn run_looper(&mut self, params: &params::AudioParams, left: &mut [f32], right: &mut [f32]) {
+        // The UI only holds the lock briefly, but if it is busy this block
+        // passes through rather than stalling the callback
+        if let Ok(mut looper) = self.looper.try_lock() {
+            looper.set_params(
+                params.looper_max_length,
+                params.looper_half_speed,
+                params.looper_reverse,
+            );
+            looper.process(left, right);
+            self.looper_playing = matches!(
+                looper.state(),
+                LooperState::Playing | LooperState::Overdubbing
+            );
+        }
+    }
+
+    fn run_looper_mono(&mut self, params: &params::AudioParams, input: &mut [f32]) {
+        let mut right = input.to_vec();
+        self.run_looper(params, input, &mut right);
+    }
+}
 
 pub fn process_audio(
     data: &[i32],
     _config: &Option<StreamConfig>,
     audio_params: &params::SharedParams,
+    processors: &mut Processors,
 ) -> Vec<i32> {
-    let params = audio_params.lock().unwrap();
+    let mut params = audio_params.lock().unwrap();
+
+    if params.tuner_enabled && params.tuner_mute {
+        return vec![0; data.len() * 2];
+    }
 
     let mut float_data: Vec<f32> = data
         .iter()
         .map(|&x| (x as f32 / i32::MAX as f32) * 1.5)
         .collect();
+    processors.safety.watch_input(&float_data);
 
     // Apply noise gate first
     if params.gate_enabled {
@@ -32,6 +189,95 @@ pub fn process_audio(
         *sample *= params.input_volume * 2.0;
     }
 
+    if params.looper_enabled && params.looper_slot == LooperSlot::Input {
+        processors.run_looper_mono(&params, &mut float_data);
+    }
+
+    if params.swell_enabled {
+        let swell = &mut processors.swell;
+        swell.set_params(params.swell_rise, params.swell_sensitivity);
+        swell.process(&mut float_data);
+    }
+
+    if params.wah_enabled {
+        let wah = &mut processors.wah;
+        wah.set_params(params.wah_position, params.wah_range, params.wah_q);
+        wah.process(&mut float_data);
+    }
+
+    if params.envelope_filter_enabled {
+        let filter = &mut processors.envelope_filter;
+        filter.set_params(
+            params.envelope_filter_mode,
+            params.envelope_filter_sensitivity,
+            params.envelope_filter_range,
+            params.envelope_filter_q,
+        );
+        filter.set_envelope(
+            params.envelope_filter_attack,
+            params.envelope_filter_decay,
+            params.envelope_filter_down,
+        );
+        filter.set_lfo(
+            params
+                .envelope_filter_lfo
+                .then_some(params.envelope_filter_rate),
+        );
+        filter.process(&mut float_data);
+    }
+
+    if params.octaver_enabled {
+        let octaver = &mut processors.octaver;
+        octaver.set_levels(
+            params.octaver_dry,
+            params.octaver_sub,
+            params.octaver_sub2,
+            params.octaver_up,
+        );
+        octaver.process(&mut float_data);
+    }
+
+    if params.pitch_enabled {
+        let shifter = &mut processors.pitch_shifter;
+        shifter.set_params(
+            params.pitch_mode,
+            params.pitch_semitones,
+            params.pitch_cents,
+            params.pitch_drop,
+            params.pitch_mix,
+        );
+        shifter.set_harmony(
+            params.pitch_key,
+            params.pitch_scale,
+            params.pitch_steps,
+            params.tuner_a4,
+        );
+        shifter.process(&mut float_data);
+    }
+
+    if params.ring_mod_enabled {
+        let ring_mod = &mut processors.ring_mod;
+        ring_mod.set_params(
+            params.ring_mod_shape,
+            params.ring_mod_frequency,
+            params.ring_mod_fine,
+            params.ring_mod_tracking,
+            params.ring_mod_mix,
+        );
+        ring_mod.set_lfo(params.ring_mod_lfo_rate, params.ring_mod_lfo_depth);
+        ring_mod.process(&mut float_data);
+    }
+
+    if params.looper_enabled && params.looper_slot == LooperSlot::PreAmp {
+        processors.run_looper_mono(&params, &mut float_data);
+    }
+
+    if params.boost_enabled && !params.boost_post {
+        let boost = &mut processors.boost;
+        boost.set_params(params.boost_gain, params.boost_tight);
+        boost.process(&mut float_data);
+    }
+
     // Apply EQ before overdrive if enabled
     if params.eq_enabled {
         let mut eq = EQ::new(44100.0);
@@ -39,24 +285,307 @@ pub fn process_audio(
         eq.process(&mut float_data);
     }
 
-    if params.overdrive_enabled {
+    if let Some(model) = &params.nam_model
+        && !processors.nam.as_ref().is_some_and(|nam| nam.uses(model))
+        && params
+            .nam_processor
+            .as_ref()
+            .is_some_and(|nam| nam.uses(model))
+    {
+        std::mem::swap(&mut processors.nam, &mut params.nam_processor);
+    }
+
+    // A loaded amp model replaces the built-in overdrive; the captured amp
+    // expects a clean DI, and the cabinet goes after it.
+    let nam_model = params.nam_model.as_ref().filter(|_| params.nam_enabled);
+
+    if params.overdrive_enabled && nam_model.is_none() {
         overdrive::apply_overdrive(
             &mut float_data,
             params.overdrive_threshold,
             params.overdrive_gain,
         );
-        if params.cabinet_enabled {
-            let cabinet = CabinetSim::new();
-            cabinet.process(&mut float_data);
+    }
+
+    if let Some(model) = nam_model
+        && let Some(nam) = processors.nam.as_mut().filter(|nam| nam.uses(model))
+    {
+        nam.process(&mut float_data);
+    }
+
+    if params.cabinet_enabled && (params.overdrive_enabled || nam_model.is_some()) {
+        let cabinet = CabinetSim::new();
+        cabinet.process(&mut float_data);
+    }
+
+    if params.boost_enabled && params.boost_post {
+        let boost = &mut processors.boost;
+        boost.set_params(params.boost_gain, params.boost_tight);
+        boost.process(&mut float_data);
+    }
+
+    if params.bitcrusher_enabled {
+        let crusher = &mut processors.bitcrusher;
+        crusher.set_params(
+            params.bitcrusher_bits,
+            params.bitcrusher_rate,
+            params.bitcrusher_anti_alias,
+            params.bitcrusher_dither,
+            params.bitcrusher_mix,
+        );
+        crusher.process(&mut float_data);
+    }
+
+    if params.volume_pedal_enabled {
+        let pedal = &mut processors.volume_pedal;
+        pedal.set_params(
+            params.volume_pedal_position,
+            params.volume_pedal_taper,
+            params.volume_pedal_minimum,
+        );
+        pedal.process(&mut float_data);
+    }
+
+    if params.looper_enabled && params.looper_slot == LooperSlot::PostAmp {
+        processors.run_looper_mono(&params, &mut float_data);
+    }
+
+    // Everything past this point runs in stereo
+    let mut left = float_data.clone();
+    let mut right = float_data;
+
+    if params.chorus_enabled {
+        let chorus = &mut processors.chorus;
+        chorus.set_params(
+            params.chorus_rate,
+            params.chorus_depth,
+            params.chorus_delay,
+            params.chorus_mix,
+            params.chorus_spread,
+        );
+        chorus.process(&mut left, &mut right);
+    }
+
+    if params.vibrato_enabled {
+        let vibrato = &mut processors.vibrato;
+        vibrato.set_params(params.vibrato_rate, params.vibrato_depth);
+        vibrato.process(&mut left, &mut right);
+    }
+
+    if params.univibe_enabled {
+        let univibe = &mut processors.univibe;
+        univibe.set_params(
+            params.univibe_rate,
+            params.univibe_depth,
+            params.univibe_mode,
+        );
+        univibe.process(&mut left, &mut right);
+    }
+
+    if params.flanger_enabled {
+        let flanger = &mut processors.flanger;
+        flanger.set_params(
+            params.flanger_rate,
+            params.flanger_depth,
+            params.flanger_manual,
+            params.flanger_feedback,
+            params.flanger_mix,
+            params.flanger_through_zero,
+        );
+        flanger.process(&mut left, &mut right);
+    }
+
+    if params.phaser_enabled {
+        let rate = if params.phaser_sync {
+            tempo::division_hz(params.tempo, params.phaser_division)
+        } else {
+            params.phaser_rate
+        };
+        let phaser = &mut processors.phaser;
+        phaser.set_params(
+            params.phaser_stages,
+            rate,
+            params.phaser_depth,
+            params.phaser_feedback,
+            params.phaser_centre,
+            params.phaser_mix,
+        );
+        phaser.process(&mut left, &mut right);
+    }
+
+    if params.tremolo_enabled {
+        let rate = if params.tremolo_sync {
+            tempo::division_hz(params.tempo, params.tremolo_division)
+        } else {
+            params.tremolo_rate
+        };
+        let tremolo = &mut processors.tremolo;
+        tremolo.set_params(
+            params.tremolo_shape,
+            rate,
+            params.tremolo_depth,
+            params.tremolo_pan,
+            params.tremolo_harmonic,
+        );
+        tremolo.process(&mut left, &mut right);
+    }
+
+    if params.rotary_enabled {
+        let rotary = &mut processors.rotary;
+        rotary.set_params(params.rotary_fast, params.rotary_spread);
+        rotary.process(&mut left, &mut right);
+    }
+
+    // Time-based effects start here; keep the dry signal around for ducking
+    let dry = params.duck_enabled.then(|| (left.clone(), right.clone()));
+
+    if params.tape_echo_enabled {
+        let echo = &mut processors.tape_echo;
+        echo.set_params(
+            params.tape_echo_time,
+            params.tape_echo_heads,
+            params.tape_echo_feedback,
+            params.tape_echo_mix,
+        );
+        echo.set_tape(
+            params.tape_echo_saturation,
+            params.tape_echo_tone,
+            params.tape_echo_wow,
+            params.tape_echo_flutter,
+        );
+        echo.process(&mut left, &mut right);
+    }
+
+    if params.bbd_enabled {
+        let bbd = &mut processors.bbd_delay;
+        bbd.set_params(params.bbd_time, params.bbd_feedback, params.bbd_mix);
+        bbd.set_character(params.bbd_mod_rate, params.bbd_mod_depth, params.bbd_noise);
+        bbd.process(&mut left, &mut right);
+    }
+
+    if params.stereo_delay_enabled {
+        let (left_time, right_time, tap_times) = if params.stereo_delay_sync {
+            (
+                tempo::division_ms(params.tempo, params.stereo_delay_left_division),
+                tempo::division_ms(params.tempo, params.stereo_delay_right_division),
+                std::array::from_fn(|i| {
+                    tempo::division_ms(params.tempo, params.stereo_delay_tap_divisions[i])
+                }),
+            )
+        } else {
+            (
+                params.stereo_delay_left,
+                params.stereo_delay_right,
+                params.stereo_delay_tap_times,
+            )
+        };
+        let delay = &mut processors.stereo_delay;
+        delay.set_params(
+            params.stereo_delay_mode,
+            left_time,
+            right_time,
+            params.stereo_delay_feedback,
+            params.stereo_delay_mix,
+        );
+        delay.set_taps(
+            params.stereo_delay_taps,
+            &tap_times,
+            &params.stereo_delay_tap_levels,
+            &params.stereo_delay_tap_pans,
+        );
+        delay.process(&mut left, &mut right);
+    }
+
+    if params.spring_enabled {
+        let spring = &mut processors.spring_reverb;
+        spring.set_params(params.spring_dwell, params.spring_tone, params.spring_mix);
+        spring.process(&mut left, &mut right);
+    }
+
+    if params.shimmer_enabled {
+        let shimmer = &mut processors.shimmer_reverb;
+        shimmer.set_params(
+            params.shimmer_interval,
+            params.shimmer_amount,
+            params.shimmer_decay,
+            params.shimmer_tone,
+            params.shimmer_mix,
+        );
+        shimmer.process(&mut left, &mut right);
+    }
+
+    if let Some(kernel) = &params.convolution_kernel
+        && !processors
+            .convolution
+            .as_ref()
+            .is_some_and(|convolution| convolution.uses(kernel))
+        && params
+            .convolution_reverb
+            .as_ref()
+            .is_some_and(|next| next.uses(kernel))
+    {
+        if let (Some(current), Some(next)) = (
+            processors.convolution.as_mut(),
+            params.convolution_reverb.as_mut(),
+        ) {
+            next.carry_over(current);
         }
+        std::mem::swap(&mut processors.convolution, &mut params.convolution_reverb);
     }
 
-    float_data
-        .iter()
-        .map(|&x| {
-            let boosted = x * params.output_volume * 3.0;
-            let limited = boosted.tanh();
-            (limited * i32::MAX as f32) as i32
-        })
+    if params.convolution_enabled
+        && let Some(kernel) = &params.convolution_kernel
+        && let Some(convolution) = processors
+            .convolution
+            .as_mut()
+            .filter(|convolution| convolution.uses(kernel))
+    {
+        convolution.set_params(
+            params.convolution_pre_delay,
+            params.convolution_width,
+            params.convolution_low_cut,
+            params.convolution_high_cut,
+            params.convolution_mix,
+        );
+        convolution.process(&mut left, &mut right);
+    }
+
+    if let Some((dry_left, dry_right)) = dry {
+        let ducker = &mut processors.ducker;
+        ducker.set_params(
+            params.duck_threshold,
+            params.duck_amount,
+            params.duck_release,
+        );
+        ducker.process((&dry_left, &dry_right), &mut left, &mut right);
+    }
+
+    if params.looper_enabled && params.looper_slot == LooperSlot::Output {
+        processors.run_looper(&params, &mut left, &mut right);
+    }
+
+    for sample in left.iter_mut().chain(right.iter_mut()) {
+        *sample *= params.output_volume * 3.0;
+    }
+
+    let muted = params.safety_muted;
+    let looping = params.looper_enabled && processors.looper_playing;
+    if processors
+        .safety
+        .process(&mut left, &mut right, muted, looping)
+    {
+        params.safety_muted = true;
+    }
+
+    if params.limiter_enabled {
+        let limiter = &mut processors.limiter;
+        limiter.set_params(params.limiter_ceiling, params.limiter_release);
+        limiter.process(&mut left, &mut right);
+    }
+
+    left.iter()
+        .zip(&right)
+        .flat_map(|(&l, &r)| [l, r])
+        .map(|x| (x.clamp(-1.0, 1.0) * i32::MAX as f32) as i32)
         .collect()
 }
@@ -0,0 +1,717 @@
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Arc;
+
+// Interface full-scale level assumed when a model carries an input calibration.
+const INTERFACE_INPUT_LEVEL_DBU: f32 = 12.2;
+// Loudness the model output is normalised to.
+const TARGET_LOUDNESS_DB: f32 = -18.0;
+// Dilations beyond this would allocate absurd history buffers on the audio thread.
+const MAX_DILATION: usize = 1 << 16;
+
+#[derive(Deserialize)]
+struct NamFile {
+    architecture: String,
+    config: serde_json::Value,
+    weights: Vec<f32>,
+    sample_rate: Option<f32>,
+    metadata: Option<NamMetadata>,
+}
+
+#[derive(Deserialize)]
+struct NamMetadata {
+    name: Option<String>,
+    loudness: Option<f32>,
+    input_level_dbu: Option<f32>,
+}
+
+#[derive(Deserialize)]
+struct WaveNetConfig {
+    layers: Vec<LayerArrayConfig>,
+    head: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct LayerArrayConfig {
+    input_size: usize,
+    condition_size: usize,
+    head_size: usize,
+    channels: usize,
+    kernel_size: usize,
+    dilations: Vec<usize>,
+    activation: String,
+    gated: bool,
+    head_bias: bool,
+}
+
+#[derive(Deserialize)]
+struct LstmConfig {
+    num_layers: usize,
+    input_size: usize,
+    hidden_size: usize,
+}
+
+struct Weights<'a> {
+    values: std::slice::Iter<'a, f32>,
+}
+
+impl Weights<'_> {
+    fn take(&mut self, count: usize) -> Result<Vec<f32>, String> {
+        let taken: Vec<f32> = self.values.by_ref().take(count).copied().collect();
+        if taken.len() == count {
+            Ok(taken)
+        } else {
+            Err("Model file has fewer weights than its config requires".to_string())
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Activation {
+    Tanh,
+    ReLU,
+    LeakyReLU,
+    Sigmoid,
+    Hardtanh,
+}
+
+impl Activation {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "Tanh" | "Fasttanh" => Ok(Activation::Tanh),
+            "ReLU" => Ok(Activation::ReLU),
+            "LeakyReLU" => Ok(Activation::LeakyReLU),
+            "Sigmoid" => Ok(Activation::Sigmoid),
+            "Hardtanh" => Ok(Activation::Hardtanh),
+            _ => Err(format!("Unsupported activation: {}", name)),
+        }
+    }
+
+    fn apply(self, x: f32) -> f32 {
+        match self {
+            Activation::Tanh => x.tanh(),
+            Activation::ReLU => x.max(0.0),
+            Activation::LeakyReLU => {
+                if x > 0.0 {
+                    x
+                } else {
+                    0.01 * x
+                }
+            }
+            Activation::Sigmoid => sigmoid(x),
+            Activation::Hardtanh => x.clamp(-1.0, 1.0),
+        }
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+struct Conv1x1 {
+    weight: Vec<f32>,
+    bias: Option<Vec<f32>>,
+    in_channels: usize,
+    out_channels: usize,
+}
+
+impl Conv1x1 {
+    fn load(
+        weights: &mut Weights,
+        in_channels: usize,
+        out_channels: usize,
+        bias: bool,
+    ) -> Result<Self, String> {
+        let weight = weights.take(in_channels * out_channels)?;
+        let bias = if bias {
+            Some(weights.take(out_channels)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            weight,
+            bias,
+            in_channels,
+            out_channels,
+        })
+    }
+
+    fn apply(&self, input: &[f32], output: &mut [f32]) {
+        for (o, out) in output.iter_mut().enumerate().take(self.out_channels) {
+            let row = &self.weight[o * self.in_channels..(o + 1) * self.in_channels];
+            let mut sum = self.bias.as_ref().map_or(0.0, |bias| bias[o]);
+            for (w, x) in row.iter().zip(input) {
+                sum += w * x;
+            }
+            *out = sum;
+        }
+    }
+}
+
+struct WaveNetLayer {
+    // Weights laid out as [kernel][out][in].
+    conv_weight: Vec<f32>,
+    conv_bias: Vec<f32>,
+    kernel_size: usize,
+    dilation: usize,
+    mixin: Conv1x1,
+    one_by_one: Conv1x1,
+}
+
+struct LayerArray {
+    rechannel: Conv1x1,
+    layers: Vec<WaveNetLayer>,
+    head_rechannel: Conv1x1,
+    channels: usize,
+    head_size: usize,
+    activation: Activation,
+    gated: bool,
+}
+
+struct WaveNet {
+    arrays: Vec<LayerArray>,
+    head_scale: f32,
+}
+
+impl WaveNet {
+    fn load(config: serde_json::Value, weights: &mut Weights) -> Result<Self, String> {
+        let config: WaveNetConfig =
+            serde_json::from_value(config).map_err(|e| format!("Invalid WaveNet config: {}", e))?;
+        if config.head.is_some_and(|head| !head.is_null()) {
+            return Err("WaveNet models with a post-head are not supported".to_string());
+        }
+
+        if config.layers.is_empty() {
+            return Err("WaveNet model has no layer arrays".to_string());
+        }
+
+        let mut arrays = Vec::with_capacity(config.layers.len());
+        for array in &config.layers {
+            if array.input_size == 0
+                || array.condition_size == 0
+                || array.head_size == 0
+                || array.channels == 0
+                || array.kernel_size == 0
+            {
+                return Err("WaveNet layer sizes must be non-zero".to_string());
+            }
+            if array.dilations.is_empty()
+                || array
+                    .dilations
+                    .iter()
+                    .any(|&dilation| dilation == 0 || dilation > MAX_DILATION)
+            {
+                return Err(format!(
+                    "WaveNet dilations must be between 1 and {}",
+                    MAX_DILATION
+                ));
+            }
+            let conv_out = if array.gated {
+                2 * array.channels
+            } else {
+                array.channels
+            };
+            let rechannel = Conv1x1::load(weights, array.input_size, array.channels, false)?;
+            let mut layers = Vec::with_capacity(array.dilations.len());
+            for &dilation in &array.dilations {
+                let raw = weights.take(conv_out * array.channels * array.kernel_size)?;
+                let mut conv_weight = vec![0.0; raw.len()];
+                let mut it = raw.into_iter();
+                for o in 0..conv_out {
+                    for i in 0..array.channels {
+                        for k in 0..array.kernel_size {
+                            conv_weight[(k * conv_out + o) * array.channels + i] =
+                                it.next().unwrap_or_default();
+                        }
+                    }
+                }
+                let conv_bias = weights.take(conv_out)?;
+                let mixin = Conv1x1::load(weights, array.condition_size, conv_out, false)?;
+                let one_by_one = Conv1x1::load(weights, array.channels, array.channels, true)?;
+                layers.push(WaveNetLayer {
+                    conv_weight,
+                    conv_bias,
+                    kernel_size: array.kernel_size,
+                    dilation,
+                    mixin,
+                    one_by_one,
+                });
+            }
+            let head_rechannel =
+                Conv1x1::load(weights, array.channels, array.head_size, array.head_bias)?;
+            arrays.push(LayerArray {
+                rechannel,
+                layers,
+                head_rechannel,
+                channels: array.channels,
+                head_size: array.head_size,
+                activation: Activation::parse(&array.activation)?,
+                gated: array.gated,
+            });
+        }
+
+        for pair in arrays.windows(2) {
+            if pair[0].head_size != pair[1].channels {
+                return Err("WaveNet head sizes do not chain between layer arrays".to_string());
+            }
+        }
+        let head_scale = weights.take(1)?[0];
+
+        Ok(Self { arrays, head_scale })
+    }
+}
+
+struct LstmCell {
+    // Weights laid out as [4 * hidden][input + hidden], gates ordered i, f, g, o.
+    weight: Vec<f32>,
+    bias: Vec<f32>,
+    initial_hidden: Vec<f32>,
+    initial_cell: Vec<f32>,
+    input_size: usize,
+    hidden_size: usize,
+}
+
+struct Lstm {
+    cells: Vec<LstmCell>,
+    head_weight: Vec<f32>,
+    head_bias: f32,
+}
+
+impl Lstm {
+    fn load(config: serde_json::Value, weights: &mut Weights) -> Result<Self, String> {
+        let config: LstmConfig =
+            serde_json::from_value(config).map_err(|e| format!("Invalid LSTM config: {}", e))?;
+        if config.num_layers == 0 || config.input_size == 0 || config.hidden_size == 0 {
+            return Err("LSTM layer sizes must be non-zero".to_string());
+        }
+        let hidden = config.hidden_size;
+
+        let mut cells = Vec::with_capacity(config.num_layers);
+        for layer in 0..config.num_layers {
+            let input_size = if layer == 0 {
+                config.input_size
+            } else {
+                hidden
+            };
+            cells.push(LstmCell {
+                weight: weights.take(4 * hidden * (input_size + hidden))?,
+                bias: weights.take(4 * hidden)?,
+                initial_hidden: weights.take(hidden)?,
+                initial_cell: weights.take(hidden)?,
+                input_size,
+                hidden_size: hidden,
+            });
+        }
+        let head_weight = weights.take(hidden)?;
+        let head_bias = weights.take(1)?[0];
+
+        Ok(Self {
+            cells,
+            head_weight,
+            head_bias,
+        })
+    }
+}
+
+enum Architecture {
+    WaveNet(WaveNet),
+    Lstm(Lstm),
+}
+
+pub struct NamModel {
+    architecture: Architecture,
+    pub name: String,
+    pub sample_rate: Option<f32>,
+    input_gain: f32,
+    output_gain: f32,
+}
+
+impl NamModel {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse(&contents, path)
+    }
+
+    // `path` only names the model and its errors.
+    fn parse(contents: &str, path: &Path) -> Result<Self, String> {
+        let file: NamFile = serde_json::from_str(contents)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+
+        let mut weights = Weights {
+            values: file.weights.iter(),
+        };
+        let architecture = match file.architecture.as_str() {
+            "WaveNet" => Architecture::WaveNet(WaveNet::load(file.config, &mut weights)?),
+            "LSTM" => Architecture::Lstm(Lstm::load(file.config, &mut weights)?),
+            other => return Err(format!("Unsupported model architecture: {}", other)),
+        };
+        if weights.values.len() != 0 {
+            return Err("Model file has more weights than its config uses".to_string());
+        }
+
+        let metadata = file.metadata.unwrap_or(NamMetadata {
+            name: None,
+            loudness: None,
+            input_level_dbu: None,
+        });
+        let input_gain = metadata
+            .input_level_dbu
+            .map_or(1.0, |level| db_to_gain(INTERFACE_INPUT_LEVEL_DBU - level));
+        let output_gain = metadata
+            .loudness
+            .map_or(1.0, |loudness| db_to_gain(TARGET_LOUDNESS_DB - loudness));
+        let name = metadata.name.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+
+        Ok(Self {
+            architecture,
+            name,
+            sample_rate: file.sample_rate,
+            input_gain,
+            output_gain,
+        })
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10.0_f32.powf(db / 20.0)
+}
+
+#[derive(Clone)]
+struct LayerState {
+    // Ring buffer of past layer inputs, `history_len` frames of `channels` values.
+    history: Vec<f32>,
+    history_len: usize,
+    position: usize,
+}
+
+#[derive(Clone)]
+enum State {
+    WaveNet {
+        layers: Vec<Vec<LayerState>>,
+        layer_buffer: Vec<f32>,
+        head_buffer: Vec<f32>,
+        conv_out: Vec<f32>,
+        mixin_out: Vec<f32>,
+        activated: Vec<f32>,
+        scratch: Vec<f32>,
+    },
+    Lstm {
+        hidden: Vec<Vec<f32>>,
+        cell: Vec<Vec<f32>>,
+        input: Vec<f32>,
+        gates: Vec<f32>,
+    },
+}
+
+#[derive(Clone)]
+pub struct NamProcessor {
+    model: Arc<NamModel>,
+    state: State,
+}
+
+impl NamProcessor {
+    pub fn new(model: Arc<NamModel>) -> Self {
+        let state = match &model.architecture {
+            Architecture::WaveNet(wavenet) => {
+                let widest = wavenet
+                    .arrays
+                    .iter()
+                    .map(|array| array.channels.max(array.head_size))
+                    .max()
+                    .unwrap_or(1)
+                    .max(1);
+                State::WaveNet {
+                    layers: wavenet
+                        .arrays
+                        .iter()
+                        .map(|array| {
+                            array
+                                .layers
+                                .iter()
+                                .map(|layer| {
+                                    let history_len = (layer.kernel_size - 1) * layer.dilation + 1;
+                                    LayerState {
+                                        history: vec![0.0; history_len * array.channels],
+                                        history_len,
+                                        position: 0,
+                                    }
+                                })
+                                .collect()
+                        })
+                        .collect(),
+                    layer_buffer: vec![0.0; widest],
+                    head_buffer: vec![0.0; widest],
+                    conv_out: vec![0.0; 2 * widest],
+                    mixin_out: vec![0.0; 2 * widest],
+                    activated: vec![0.0; widest],
+                    scratch: vec![0.0; widest],
+                }
+            }
+            Architecture::Lstm(lstm) => {
+                let widest = lstm
+                    .cells
+                    .iter()
+                    .map(|cell| cell.input_size + cell.hidden_size)
+                    .max()
+                    .unwrap_or(1);
+                State::Lstm {
+                    hidden: lstm
+                        .cells
+                        .iter()
+                        .map(|cell| cell.initial_hidden.clone())
+                        .collect(),
+                    cell: lstm
+                        .cells
+                        .iter()
+                        .map(|cell| cell.initial_cell.clone())
+                        .collect(),
+                    input: vec![0.0; widest],
+                    gates: vec![0.0; 4 * widest],
+                }
+            }
+        };
+
+        Self { model, state }
+    }
+
+    pub fn uses(&self, model: &Arc<NamModel>) -> bool {
+        Arc::ptr_eq(&self.model, model)
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        let input_gain = self.model.input_gain;
+        let output_gain = self.model.output_gain;
+
+        match (&self.model.architecture, &mut self.state) {
+            (
+                Architecture::WaveNet(wavenet),
+                State::WaveNet {
+                    layers,
+                    layer_buffer,
+                    head_buffer,
+                    conv_out,
+                    mixin_out,
+                    activated,
+                    scratch,
+                },
+            ) => {
+                for sample in input.iter_mut() {
+                    let condition = [*sample * input_gain];
+                    layer_buffer[0] = condition[0];
+                    head_buffer.fill(0.0);
+
+                    for (array, states) in wavenet.arrays.iter().zip(layers.iter_mut()) {
+                        let channels = array.channels;
+                        let conv_channels = if array.gated { 2 * channels } else { channels };
+
+                        array.rechannel.apply(layer_buffer, scratch);
+                        layer_buffer[..channels].copy_from_slice(&scratch[..channels]);
+
+                        for (layer, state) in array.layers.iter().zip(states.iter_mut()) {
+                            let frame = state.position * channels;
+                            state.history[frame..frame + channels]
+                                .copy_from_slice(&layer_buffer[..channels]);
+
+                            conv_out[..conv_channels].copy_from_slice(&layer.conv_bias);
+                            for k in 0..layer.kernel_size {
+                                let delay = (layer.kernel_size - 1 - k) * layer.dilation;
+                                let index = (state.position + state.history_len - delay)
+                                    % state.history_len;
+                                let past = &state.history[index * channels..(index + 1) * channels];
+                                let tap = &layer.conv_weight[k * conv_channels * channels
+                                    ..(k + 1) * conv_channels * channels];
+                                for (o, out) in conv_out[..conv_channels].iter_mut().enumerate() {
+                                    let row = &tap[o * channels..(o + 1) * channels];
+                                    *out += row.iter().zip(past).map(|(w, x)| w * x).sum::<f32>();
+                                }
+                            }
+                            state.position = (state.position + 1) % state.history_len;
+
+                            layer.mixin.apply(&condition, mixin_out);
+                            for c in 0..channels {
+                                let top = array.activation.apply(conv_out[c] + mixin_out[c]);
+                                activated[c] = if array.gated {
+                                    top * sigmoid(conv_out[c + channels] + mixin_out[c + channels])
+                                } else {
+                                    top
+                                };
+                                head_buffer[c] += activated[c];
+                            }
+
+                            layer.one_by_one.apply(activated, scratch);
+                            for c in 0..channels {
+                                layer_buffer[c] += scratch[c];
+                            }
+                        }
+
+                        array.head_rechannel.apply(head_buffer, scratch);
+                        head_buffer.fill(0.0);
+                        head_buffer[..array.head_size].copy_from_slice(&scratch[..array.head_size]);
+                    }
+
+                    *sample = head_buffer[0] * wavenet.head_scale * output_gain;
+                }
+            }
+            (
+                Architecture::Lstm(lstm),
+                State::Lstm {
+                    hidden,
+                    cell,
+                    input: xh,
+                    gates,
+                },
+            ) => {
+                for sample in input.iter_mut() {
+                    xh[0] = *sample * input_gain;
+
+                    for (layer, lstm_cell) in lstm.cells.iter().enumerate() {
+                        let size = lstm_cell.hidden_size;
+                        let width = lstm_cell.input_size + size;
+                        if layer > 0 {
+                            xh[..size].copy_from_slice(&hidden[layer - 1]);
+                        }
+                        xh[lstm_cell.input_size..width].copy_from_slice(&hidden[layer]);
+
+                        for (g, gate) in gates[..4 * size].iter_mut().enumerate() {
+                            let row = &lstm_cell.weight[g * width..(g + 1) * width];
+                            *gate = lstm_cell.bias[g]
+                                + row
+                                    .iter()
+                                    .zip(&xh[..width])
+                                    .map(|(w, x)| w * x)
+                                    .sum::<f32>();
+                        }
+
+                        for h in 0..size {
+                            let input_gate = sigmoid(gates[h]);
+                            let forget_gate = sigmoid(gates[size + h]);
+                            let cell_gate = gates[2 * size + h].tanh();
+                            let output_gate = sigmoid(gates[3 * size + h]);
+                            cell[layer][h] = forget_gate * cell[layer][h] + input_gate * cell_gate;
+                            hidden[layer][h] = output_gate * cell[layer][h].tanh();
+                        }
+                    }
+
+                    let last = hidden.last().map(Vec::as_slice).unwrap_or_default();
+                    let out = lstm.head_bias
+                        + lstm
+                            .head_weight
+                            .iter()
+                            .zip(last)
+                            .map(|(w, h)| w * h)
+                            .sum::<f32>();
+                    *sample = out * output_gain;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> Result<NamModel, String> {
+        NamModel::parse(json, Path::new("test.nam"))
+    }
+
+    fn run(model: NamModel, input: &[f32]) -> Vec<f32> {
+        let mut output = input.to_vec();
+        NamProcessor::new(Arc::new(model)).process(&mut output);
+        output
+    }
+
+    fn assert_close(actual: &[f32], expected: &[f32]) {
+        for (n, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!((a - e).abs() < 1e-6, "sample {n}: {a} != {e}");
+        }
+    }
+
+    // One layer, one channel, kernel 2 at dilation 2. Weights in file order:
+    // rechannel, conv [out][in][kernel], conv bias, mixin, 1x1 weight and
+    // bias, head rechannel, head scale.
+    fn wavenet(weights: &str) -> String {
+        format!(
+            r#"{{
+                "architecture": "WaveNet",
+                "config": {{
+                    "layers": [{{
+                        "input_size": 1, "condition_size": 1, "head_size": 1,
+                        "channels": 1, "kernel_size": 2, "dilations": [2],
+                        "activation": "Tanh", "gated": false, "head_bias": false
+                    }}],
+                    "head": null
+                }},
+                "weights": [{weights}]
+            }}"#
+        )
+    }
+
+    #[test]
+    fn wavenet_matches_hand_computed_output() {
+        let model = parse(&wavenet("0.5, 0.3, -0.2, 0.1, 0.4, 1.0, 0.0, 2.0, 0.5")).unwrap();
+        // head * scale * tanh(bias + rechannel * (0.3 x[n-2] - 0.2 x[n]) + mixin * x[n])
+        //   = tanh(0.1 + 0.15 x[n-2] + 0.3 x[n])
+        let output = run(model, &[1.0, 0.0, -1.0, 0.5]);
+        assert_close(
+            &output,
+            &[
+                0.4_f32.tanh(),
+                0.1_f32.tanh(),
+                (-0.05_f32).tanh(),
+                0.25_f32.tanh(),
+            ],
+        );
+    }
+
+    #[test]
+    fn lstm_matches_hand_computed_output() {
+        // Gate rows i, f, g, o over [x, h], then biases, initial h and c, head
+        let model = parse(
+            r#"{
+                "architecture": "LSTM",
+                "config": { "num_layers": 1, "input_size": 1, "hidden_size": 1 },
+                "weights": [
+                    0.5, 0.1, 0.2, -0.3, 1.0, 0.4, -0.6, 0.2,
+                    0.0, 1.0, 0.0, 0.5,
+                    0.2, -0.1,
+                    2.0, 0.1
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let input = [1.0, -0.5, 0.25];
+        let (mut h, mut c) = (0.2_f32, -0.1_f32);
+        let expected: Vec<f32> = input
+            .iter()
+            .map(|&x| {
+                let i = sigmoid(0.5 * x + 0.1 * h);
+                let f = sigmoid(0.2 * x - 0.3 * h + 1.0);
+                let g = (1.0 * x + 0.4 * h).tanh();
+                let o = sigmoid(-0.6 * x + 0.2 * h + 0.5);
+                c = f * c + i * g;
+                h = o * c.tanh();
+                2.0 * h + 0.1
+            })
+            .collect();
+        assert_close(&run(model, &input), &expected);
+    }
+
+    #[test]
+    fn weight_count_mismatch_is_rejected() {
+        assert!(parse(&wavenet("0.5, 0.3, -0.2, 0.1, 0.4, 1.0, 0.0, 2.0")).is_err());
+        assert!(
+            parse(&wavenet(
+                "0.5, 0.3, -0.2, 0.1, 0.4, 1.0, 0.0, 2.0, 0.5, 0.0"
+            ))
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,18 @@
+// Cheap xorshift white noise, good enough for dither and analog hiss.
+pub struct Noise {
+    state: u32,
+}
+
+impl Noise {
+    pub fn new(seed: u32) -> Self {
+        Self { state: seed.max(1) }
+    }
+
+    // Uniform in -1..1
+    pub fn next(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        self.state as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
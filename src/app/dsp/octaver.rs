@@ -0,0 +1,90 @@
+use super::envelope::EnvelopeFollower;
+use super::svf::StateVariableFilter;
+
+const TRACKING_HZ: f32 = 500.0;
+const SUB_TONE_HZ: f32 = 600.0;
+const DC_BLOCK_HZ: f32 = 30.0;
+// Zero-crossing hysteresis relative to the envelope, so chords and pick noise
+// don't make the dividers chatter.
+const HYSTERESIS: f32 = 0.15;
+
+pub struct Octaver {
+    dry: f32,
+    sub: f32,
+    sub2: f32,
+    up: f32,
+    sample_rate: f32,
+    tracking: StateVariableFilter,
+    follower: EnvelopeFollower,
+    positive: bool,
+    divider: [bool; 2],
+    sub_tone: [StateVariableFilter; 2],
+    rectified_dc: f32,
+}
+
+impl Octaver {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            dry: 1.0,
+            sub: 0.5,
+            sub2: 0.0,
+            up: 0.0,
+            sample_rate,
+            tracking: StateVariableFilter::default(),
+            follower: EnvelopeFollower::new(sample_rate, 0.002, 0.05),
+            positive: false,
+            divider: [false; 2],
+            sub_tone: [
+                StateVariableFilter::default(),
+                StateVariableFilter::default(),
+            ],
+            rectified_dc: 0.0,
+        }
+    }
+
+    // Levels for the dry signal, one and two octaves down and one octave up.
+    pub fn set_levels(&mut self, dry: f32, sub: f32, sub2: f32, up: f32) {
+        self.dry = dry;
+        self.sub = sub;
+        self.sub2 = sub2;
+        self.up = up;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        let dc_alpha = 1.0 - (-std::f32::consts::TAU * DC_BLOCK_HZ / self.sample_rate).exp();
+
+        for sample in input.iter_mut() {
+            let x = *sample;
+            let envelope = self.follower.process(x.abs());
+            let tracked = self
+                .tracking
+                .process(x, TRACKING_HZ, 0.7, self.sample_rate)
+                .low;
+
+            // Flip-flop dividers clocked by rising zero crossings.
+            let threshold = HYSTERESIS * envelope;
+            if !self.positive && tracked > threshold {
+                self.positive = true;
+                self.divider[0] = !self.divider[0];
+                if self.divider[0] {
+                    self.divider[1] = !self.divider[1];
+                }
+            } else if self.positive && tracked < -threshold {
+                self.positive = false;
+            }
+
+            let mut subs = [0.0; 2];
+            for ((sub, divider), tone) in subs.iter_mut().zip(self.divider).zip(&mut self.sub_tone)
+            {
+                let square = if divider { envelope } else { -envelope };
+                *sub = tone.process(square, SUB_TONE_HZ, 0.7, self.sample_rate).low;
+            }
+
+            let rectified = x.abs();
+            self.rectified_dc += dc_alpha * (rectified - self.rectified_dc);
+            let up = rectified - self.rectified_dc;
+
+            *sample = self.dry * x + self.sub * subs[0] + self.sub2 * subs[1] + self.up * up;
+        }
+    }
+}
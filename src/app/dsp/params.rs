@@ -1,5 +1,17 @@
 use std::sync::{Arc, Mutex};
 
+use super::convolution::{ConvolutionKernel, ConvolutionReverb};
+use super::envelope_filter::FilterMode;
+use super::lfo::LfoShape;
+use super::looper::LooperSlot;
+use super::nam::{NamModel, NamProcessor};
+use super::pitch_shift::{PitchMode, Scale};
+use super::shimmer_reverb::ShimmerInterval;
+use super::stereo_delay::{DelayMode, MAX_TAPS};
+use super::univibe::VibeMode;
+use super::volume_pedal::Taper;
+use super::wah::WahRange;
+
 #[derive(Clone)]
 pub struct AudioParams {
     pub input_volume: f32,
@@ -13,7 +25,172 @@ pub struct AudioParams {
     pub eq_high: f32,
     pub gate_enabled: bool,
     pub gate_threshold: f32,
+    pub wah_enabled: bool,
+    pub wah_position: f32,
+    pub wah_range: WahRange,
+    pub wah_q: f32,
+    pub wah_cc: u8,
+    pub envelope_filter_enabled: bool,
+    pub envelope_filter_mode: FilterMode,
+    pub envelope_filter_sensitivity: f32,
+    pub envelope_filter_range: f32,
+    pub envelope_filter_q: f32,
+    pub envelope_filter_attack: f32,
+    pub envelope_filter_decay: f32,
+    pub envelope_filter_down: bool,
+    pub envelope_filter_lfo: bool,
+    pub envelope_filter_rate: f32,
+    pub octaver_enabled: bool,
+    pub octaver_dry: f32,
+    pub octaver_sub: f32,
+    pub octaver_sub2: f32,
+    pub octaver_up: f32,
+    pub pitch_enabled: bool,
+    pub pitch_mode: PitchMode,
+    pub pitch_semitones: i32,
+    pub pitch_cents: f32,
+    pub pitch_drop: i32,
+    pub pitch_mix: f32,
+    pub pitch_key: usize,
+    pub pitch_scale: Scale,
+    pub pitch_steps: i32,
+    pub ring_mod_enabled: bool,
+    pub ring_mod_shape: LfoShape,
+    pub ring_mod_frequency: f32,
+    pub ring_mod_fine: f32,
+    pub ring_mod_lfo_rate: f32,
+    pub ring_mod_lfo_depth: f32,
+    pub ring_mod_tracking: bool,
+    pub ring_mod_mix: f32,
+    pub boost_enabled: bool,
+    pub boost_gain: f32,
+    pub boost_tight: bool,
+    pub boost_post: bool,
+    pub bitcrusher_enabled: bool,
+    pub bitcrusher_bits: f32,
+    pub bitcrusher_rate: f32,
+    pub bitcrusher_anti_alias: bool,
+    pub bitcrusher_dither: bool,
+    pub bitcrusher_mix: f32,
+    pub volume_pedal_enabled: bool,
+    pub volume_pedal_position: f32,
+    pub volume_pedal_taper: Taper,
+    pub volume_pedal_minimum: f32,
     pub cabinet_enabled: bool,
+    pub tempo: f32,
+    pub tuner_enabled: bool,
+    pub tuner_mute: bool,
+    pub tuner_a4: f32,
+    pub swell_enabled: bool,
+    pub swell_rise: f32,
+    pub swell_sensitivity: f32,
+    pub nam_enabled: bool,
+    pub nam_model: Option<Arc<NamModel>>,
+    // Built by the UI for `nam_model`; the audio thread swaps it with its own
+    // and the one it was using waits here to be freed off the audio thread.
+    pub nam_processor: Option<NamProcessor>,
+    pub chorus_enabled: bool,
+    pub chorus_rate: f32,
+    pub chorus_depth: f32,
+    pub chorus_delay: f32,
+    pub chorus_mix: f32,
+    pub chorus_spread: f32,
+    pub vibrato_enabled: bool,
+    pub vibrato_rate: f32,
+    pub vibrato_depth: f32,
+    pub univibe_enabled: bool,
+    pub univibe_rate: f32,
+    pub univibe_depth: f32,
+    pub univibe_mode: VibeMode,
+    pub flanger_enabled: bool,
+    pub flanger_rate: f32,
+    pub flanger_depth: f32,
+    pub flanger_manual: f32,
+    pub flanger_feedback: f32,
+    pub flanger_mix: f32,
+    pub flanger_through_zero: bool,
+    pub phaser_enabled: bool,
+    pub phaser_stages: usize,
+    pub phaser_rate: f32,
+    pub phaser_depth: f32,
+    pub phaser_feedback: f32,
+    pub phaser_centre: f32,
+    pub phaser_mix: f32,
+    pub phaser_sync: bool,
+    pub phaser_division: usize,
+    pub tremolo_enabled: bool,
+    pub tremolo_shape: LfoShape,
+    pub tremolo_rate: f32,
+    pub tremolo_depth: f32,
+    pub tremolo_sync: bool,
+    pub tremolo_division: usize,
+    pub tremolo_pan: bool,
+    pub tremolo_harmonic: bool,
+    pub rotary_enabled: bool,
+    pub rotary_fast: bool,
+    pub rotary_spread: f32,
+    pub tape_echo_enabled: bool,
+    pub tape_echo_time: f32,
+    pub tape_echo_heads: usize,
+    pub tape_echo_feedback: f32,
+    pub tape_echo_saturation: f32,
+    pub tape_echo_tone: f32,
+    pub tape_echo_wow: f32,
+    pub tape_echo_flutter: f32,
+    pub tape_echo_mix: f32,
+    pub bbd_enabled: bool,
+    pub bbd_time: f32,
+    pub bbd_feedback: f32,
+    pub bbd_mix: f32,
+    pub bbd_mod_rate: f32,
+    pub bbd_mod_depth: f32,
+    pub bbd_noise: f32,
+    pub stereo_delay_enabled: bool,
+    pub stereo_delay_mode: DelayMode,
+    pub stereo_delay_left: f32,
+    pub stereo_delay_right: f32,
+    pub stereo_delay_feedback: f32,
+    pub stereo_delay_mix: f32,
+    pub stereo_delay_sync: bool,
+    pub stereo_delay_left_division: usize,
+    pub stereo_delay_right_division: usize,
+    pub stereo_delay_taps: usize,
+    pub stereo_delay_tap_times: [f32; MAX_TAPS],
+    pub stereo_delay_tap_divisions: [usize; MAX_TAPS],
+    pub stereo_delay_tap_levels: [f32; MAX_TAPS],
+    pub stereo_delay_tap_pans: [f32; MAX_TAPS],
+    pub spring_enabled: bool,
+    pub spring_dwell: f32,
+    pub spring_tone: f32,
+    pub spring_mix: f32,
+    pub shimmer_enabled: bool,
+    pub shimmer_interval: ShimmerInterval,
+    pub shimmer_amount: f32,
+    pub shimmer_decay: f32,
+    pub shimmer_tone: f32,
+    pub shimmer_mix: f32,
+    pub convolution_enabled: bool,
+    pub convolution_kernel: Option<Arc<ConvolutionKernel>>,
+    // Built by the UI for `convolution_kernel` and swapped in like `nam_processor`.
+    pub convolution_reverb: Option<ConvolutionReverb>,
+    pub convolution_pre_delay: f32,
+    pub convolution_width: f32,
+    pub convolution_low_cut: f32,
+    pub convolution_high_cut: f32,
+    pub convolution_mix: f32,
+    pub duck_enabled: bool,
+    pub duck_threshold: f32,
+    pub duck_amount: f32,
+    pub duck_release: f32,
+    pub looper_enabled: bool,
+    pub looper_slot: LooperSlot,
+    pub looper_max_length: f32,
+    pub looper_half_speed: bool,
+    pub looper_reverse: bool,
+    pub limiter_enabled: bool,
+    pub limiter_ceiling: f32,
+    pub limiter_release: f32,
+    pub safety_muted: bool,
 }
 
 pub type SharedParams = Arc<Mutex<AudioParams>>;
@@ -39,6 +216,168 @@ impl AudioParams {
             eq_high: 1.0,
             gate_enabled: false,
             gate_threshold: -40.0,
+            wah_enabled: false,
+            wah_position: 0.5,
+            wah_range: WahRange::CryBaby,
+            wah_q: 5.0,
+            wah_cc: 11,
+            envelope_filter_enabled: false,
+            envelope_filter_mode: FilterMode::BandPass,
+            envelope_filter_sensitivity: 0.5,
+            envelope_filter_range: 3.0,
+            envelope_filter_q: 4.0,
+            envelope_filter_attack: 5.0,
+            envelope_filter_decay: 100.0,
+            envelope_filter_down: false,
+            envelope_filter_lfo: false,
+            envelope_filter_rate: 1.0,
+            octaver_enabled: false,
+            octaver_dry: 1.0,
+            octaver_sub: 0.5,
+            octaver_sub2: 0.0,
+            octaver_up: 0.0,
+            pitch_enabled: false,
+            pitch_mode: PitchMode::Shift,
+            pitch_semitones: 12,
+            pitch_cents: 0.0,
+            pitch_drop: 1,
+            pitch_mix: 0.5,
+            pitch_key: 0,
+            pitch_scale: Scale::Major,
+            pitch_steps: 2,
+            ring_mod_enabled: false,
+            ring_mod_shape: LfoShape::Sine,
+            ring_mod_frequency: 440.0,
+            ring_mod_fine: 0.0,
+            ring_mod_lfo_rate: 0.5,
+            ring_mod_lfo_depth: 0.0,
+            ring_mod_tracking: false,
+            ring_mod_mix: 1.0,
+            boost_enabled: false,
+            boost_gain: 6.0,
+            boost_tight: false,
+            boost_post: false,
+            bitcrusher_enabled: false,
+            bitcrusher_bits: 8.0,
+            bitcrusher_rate: 11025.0,
+            bitcrusher_anti_alias: true,
+            bitcrusher_dither: false,
+            bitcrusher_mix: 1.0,
+            volume_pedal_enabled: false,
+            volume_pedal_position: 1.0,
+            volume_pedal_taper: Taper::Audio,
+            volume_pedal_minimum: 0.0,
+            tempo: 120.0,
+            tuner_enabled: false,
+            tuner_mute: false,
+            tuner_a4: 440.0,
+            swell_enabled: false,
+            swell_rise: 0.6,
+            swell_sensitivity: 0.5,
+            nam_enabled: false,
+            nam_model: None,
+            nam_processor: None,
+            chorus_enabled: false,
+            chorus_rate: 0.8,
+            chorus_depth: 2.0,
+            chorus_delay: 12.0,
+            chorus_mix: 0.5,
+            chorus_spread: 0.0,
+            vibrato_enabled: false,
+            vibrato_rate: 5.0,
+            vibrato_depth: 1.0,
+            univibe_enabled: false,
+            univibe_rate: 2.0,
+            univibe_depth: 0.7,
+            univibe_mode: VibeMode::Chorus,
+            flanger_enabled: false,
+            flanger_rate: 0.3,
+            flanger_depth: 0.7,
+            flanger_manual: 2.0,
+            flanger_feedback: 0.5,
+            flanger_mix: 0.5,
+            flanger_through_zero: false,
+            phaser_enabled: false,
+            phaser_stages: 4,
+            phaser_rate: 0.5,
+            phaser_depth: 0.7,
+            phaser_feedback: 0.3,
+            phaser_centre: 800.0,
+            phaser_mix: 0.5,
+            phaser_sync: false,
+            phaser_division: 2,
+            tremolo_enabled: false,
+            tremolo_shape: LfoShape::Sine,
+            tremolo_rate: 5.0,
+            tremolo_depth: 0.5,
+            tremolo_sync: false,
+            tremolo_division: 4,
+            tremolo_pan: false,
+            tremolo_harmonic: false,
+            rotary_enabled: false,
+            rotary_fast: false,
+            rotary_spread: 0.7,
+            tape_echo_enabled: false,
+            tape_echo_time: 300.0,
+            tape_echo_heads: 0,
+            tape_echo_feedback: 0.4,
+            tape_echo_saturation: 0.5,
+            tape_echo_tone: 0.5,
+            tape_echo_wow: 0.3,
+            tape_echo_flutter: 0.3,
+            tape_echo_mix: 0.4,
+            bbd_enabled: false,
+            bbd_time: 300.0,
+            bbd_feedback: 0.4,
+            bbd_mix: 0.4,
+            bbd_mod_rate: 0.5,
+            bbd_mod_depth: 0.0,
+            bbd_noise: 0.5,
+            stereo_delay_enabled: false,
+            stereo_delay_mode: DelayMode::Stereo,
+            stereo_delay_left: 375.0,
+            stereo_delay_right: 500.0,
+            stereo_delay_feedback: 0.4,
+            stereo_delay_mix: 0.4,
+            stereo_delay_sync: false,
+            stereo_delay_left_division: 3,
+            stereo_delay_right_division: 2,
+            stereo_delay_taps: 4,
+            stereo_delay_tap_times: [125.0, 250.0, 375.0, 500.0, 625.0, 750.0, 875.0, 1000.0],
+            stereo_delay_tap_divisions: [6, 4, 3, 2, 2, 1, 1, 0],
+            stereo_delay_tap_levels: [1.0, 0.8, 0.6, 0.5, 0.4, 0.3, 0.25, 0.2],
+            stereo_delay_tap_pans: [-0.8, 0.8, -0.5, 0.5, -0.3, 0.3, -0.1, 0.1],
+            spring_enabled: false,
+            spring_dwell: 0.5,
+            spring_tone: 0.5,
+            spring_mix: 0.3,
+            shimmer_enabled: false,
+            shimmer_interval: ShimmerInterval::OctaveUp,
+            shimmer_amount: 0.5,
+            shimmer_decay: 4.0,
+            shimmer_tone: 0.5,
+            shimmer_mix: 0.4,
+            convolution_enabled: false,
+            convolution_kernel: None,
+            convolution_reverb: None,
+            convolution_pre_delay: 20.0,
+            convolution_width: 1.0,
+            convolution_low_cut: 100.0,
+            convolution_high_cut: 8000.0,
+            convolution_mix: 0.3,
+            duck_enabled: false,
+            duck_threshold: -30.0,
+            duck_amount: 0.7,
+            duck_release: 0.3,
+            looper_enabled: false,
+            looper_slot: LooperSlot::Output,
+            looper_max_length: 60.0,
+            looper_half_speed: false,
+            looper_reverse: false,
+            limiter_enabled: true,
+            limiter_ceiling: -0.3,
+            limiter_release: 100.0,
+            safety_muted: false,
         }
     }
 }
@@ -0,0 +1,93 @@
+pub const STAGE_OPTIONS: [usize; 4] = [4, 6, 8, 12];
+const MAX_STAGES: usize = 12;
+const SWEEP_OCTAVES: f32 = 2.0;
+
+#[derive(Clone, Copy, Default)]
+pub(super) struct AllPass {
+    x1: f32,
+    y1: f32,
+}
+
+impl AllPass {
+    pub(super) fn process(&mut self, input: f32, coef: f32) -> f32 {
+        let output = coef * input + self.x1 - coef * self.y1;
+        self.x1 = input;
+        self.y1 = output;
+        output
+    }
+}
+
+pub struct Phaser {
+    stages: usize,
+    rate: f32,
+    depth: f32,
+    feedback: f32,
+    centre: f32,
+    mix: f32,
+    sample_rate: f32,
+    phase: f32,
+    filters: [[AllPass; MAX_STAGES]; 2],
+    last_wet: [f32; 2],
+}
+
+impl Phaser {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            stages: 4,
+            rate: 0.5,
+            depth: 0.7,
+            feedback: 0.3,
+            centre: 800.0,
+            mix: 0.5,
+            sample_rate,
+            phase: 0.0,
+            filters: [[AllPass::default(); MAX_STAGES]; 2],
+            last_wet: [0.0; 2],
+        }
+    }
+
+    // Rate in Hz, depth 0..1, feedback -1..1, centre frequency in Hz.
+    pub fn set_params(
+        &mut self,
+        stages: usize,
+        rate: f32,
+        depth: f32,
+        feedback: f32,
+        centre: f32,
+        mix: f32,
+    ) {
+        self.stages = stages.clamp(1, MAX_STAGES);
+        self.rate = rate;
+        self.depth = depth;
+        self.feedback = feedback.clamp(-0.95, 0.95);
+        self.centre = centre;
+        self.mix = mix;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let phase_step = self.rate / self.sample_rate;
+        let nyquist = 0.49 * self.sample_rate;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let lfo = (std::f32::consts::TAU * self.phase).sin();
+            let freq = (self.centre * 2.0_f32.powf(self.depth * SWEEP_OCTAVES * lfo)).min(nyquist);
+            let t = (std::f32::consts::PI * freq / self.sample_rate).tan();
+            let coef = (t - 1.0) / (t + 1.0);
+
+            for ((sample, filters), last_wet) in [l, r]
+                .into_iter()
+                .zip(&mut self.filters)
+                .zip(&mut self.last_wet)
+            {
+                let mut wet = *sample + self.feedback * *last_wet;
+                for filter in filters.iter_mut().take(self.stages) {
+                    wet = filter.process(wet, coef);
+                }
+                *last_wet = wet;
+                *sample = *sample * (1.0 - self.mix) + wet * self.mix;
+            }
+
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
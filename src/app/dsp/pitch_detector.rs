@@ -0,0 +1,122 @@
+pub const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+const WINDOW_MS: f32 = 25.0;
+const MIN_FREQ: f32 = 60.0;
+const HOP_MS: f32 = 20.0;
+const YIN_THRESHOLD: f32 = 0.15;
+const SILENCE_RMS: f32 = 0.005;
+
+// Fractional MIDI note number for a frequency, given the A4 reference.
+pub fn frequency_to_note(freq: f32, a4: f32) -> f32 {
+    69.0 + 12.0 * (freq / a4).log2()
+}
+
+// YIN pitch detector over a sliding window of the most recent input.
+pub struct PitchDetector {
+    sample_rate: f32,
+    window: usize,
+    max_lag: usize,
+    hop: usize,
+    history: Vec<f32>,
+    write_pos: usize,
+    since_detect: usize,
+    frame: Vec<f32>,
+    difference: Vec<f32>,
+    pitch: Option<f32>,
+}
+
+impl PitchDetector {
+    pub fn new(sample_rate: f32) -> Self {
+        let window = (WINDOW_MS * 0.001 * sample_rate) as usize;
+        let max_lag = (sample_rate / MIN_FREQ) as usize;
+        Self {
+            sample_rate,
+            window,
+            max_lag,
+            hop: (HOP_MS * 0.001 * sample_rate) as usize,
+            history: vec![0.0; window + max_lag],
+            write_pos: 0,
+            since_detect: 0,
+            frame: vec![0.0; window + max_lag],
+            difference: vec![0.0; max_lag + 1],
+            pitch: None,
+        }
+    }
+
+    // Feeds input and returns the latest estimate in Hz, or `None` when the
+    // signal is too quiet or has no clear periodicity.
+    pub fn process(&mut self, input: &[f32]) -> Option<f32> {
+        for &sample in input {
+            self.history[self.write_pos] = sample;
+            self.write_pos = (self.write_pos + 1) % self.history.len();
+            self.since_detect += 1;
+        }
+
+        if self.since_detect >= self.hop {
+            self.since_detect = 0;
+            self.pitch = self.detect();
+        }
+        self.pitch
+    }
+
+    fn detect(&mut self) -> Option<f32> {
+        let len = self.history.len();
+        for (i, value) in self.frame.iter_mut().enumerate() {
+            *value = self.history[(self.write_pos + i) % len];
+        }
+
+        let energy: f32 = self.frame[..self.window].iter().map(|x| x * x).sum();
+        if (energy / self.window as f32).sqrt() < SILENCE_RMS {
+            return None;
+        }
+
+        // Cumulative mean normalised difference function
+        self.difference[0] = 1.0;
+        let mut running_sum = 0.0;
+        for lag in 1..=self.max_lag {
+            let diff: f32 = self.frame[..self.window]
+                .iter()
+                .zip(&self.frame[lag..lag + self.window])
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum();
+            running_sum += diff;
+            self.difference[lag] = if running_sum > 0.0 {
+                diff * lag as f32 / running_sum
+            } else {
+                1.0
+            };
+        }
+
+        let min_lag = (self.sample_rate / 1500.0) as usize;
+        let mut lag = min_lag.max(2);
+        while lag < self.max_lag {
+            if self.difference[lag] < YIN_THRESHOLD {
+                while lag + 1 < self.max_lag && self.difference[lag + 1] < self.difference[lag] {
+                    lag += 1;
+                }
+                break;
+            }
+            lag += 1;
+        }
+        if lag >= self.max_lag {
+            return None;
+        }
+
+        // Parabolic interpolation around the minimum
+        let (prev, here, next) = (
+            self.difference[lag - 1],
+            self.difference[lag],
+            self.difference[lag + 1],
+        );
+        let denominator = prev - 2.0 * here + next;
+        let offset = if denominator.abs() > f32::EPSILON {
+            0.5 * (prev - next) / denominator
+        } else {
+            0.0
+        };
+
+        Some(self.sample_rate / (lag as f32 + offset))
+    }
+}
@@ -0,0 +1,166 @@
+use super::delay_line::DelayLine;
+use super::pitch_detector::{PitchDetector, frequency_to_note};
+
+const GRAIN_MS: f32 = 50.0;
+const GLIDE_MS: f32 = 20.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PitchMode {
+    Shift,
+    Drop,
+    Harmony,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Scale {
+    Major,
+    Minor,
+    HarmonicMinor,
+    Dorian,
+    Mixolydian,
+}
+
+impl Scale {
+    pub const ALL: [Scale; 5] = [
+        Scale::Major,
+        Scale::Minor,
+        Scale::HarmonicMinor,
+        Scale::Dorian,
+        Scale::Mixolydian,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Scale::Major => "Major",
+            Scale::Minor => "Minor",
+            Scale::HarmonicMinor => "Harmonic minor",
+            Scale::Dorian => "Dorian",
+            Scale::Mixolydian => "Mixolydian",
+        }
+    }
+
+    fn degrees(self) -> [i32; 7] {
+        match self {
+            Scale::Major => [0, 2, 4, 5, 7, 9, 11],
+            Scale::Minor => [0, 2, 3, 5, 7, 8, 10],
+            Scale::HarmonicMinor => [0, 2, 3, 5, 7, 8, 11],
+            Scale::Dorian => [0, 2, 3, 5, 7, 9, 10],
+            Scale::Mixolydian => [0, 2, 4, 5, 7, 9, 10],
+        }
+    }
+
+    // Semitones from `note` to the note `steps` scale degrees away in `key`.
+    // Notes outside the scale are treated as the degree just below them.
+    fn interval(self, note: i32, key: usize, steps: i32) -> i32 {
+        let degrees = self.degrees();
+        let relative = (note - key as i32).rem_euclid(12);
+        let degree = degrees.iter().rposition(|&d| d <= relative).unwrap_or(0);
+        let target = degree as i32 + steps;
+        degrees[target.rem_euclid(7) as usize] + 12 * target.div_euclid(7) - degrees[degree]
+    }
+}
+
+// Harmony intervals offered in the UI, as (label, scale steps).
+pub const HARMONY_INTERVALS: [(&str, i32); 9] = [
+    ("Octave below", -7),
+    ("5th below", -4),
+    ("4th below", -3),
+    ("3rd below", -2),
+    ("3rd above", 2),
+    ("4th above", 3),
+    ("5th above", 4),
+    ("6th above", 5),
+    ("Octave above", 7),
+];
+
+pub struct PitchShifter {
+    mode: PitchMode,
+    semitones: f32,
+    mix: f32,
+    key: usize,
+    scale: Scale,
+    steps: i32,
+    a4: f32,
+    sample_rate: f32,
+    grain: f32,
+    phase: f32,
+    current_semitones: f32,
+    line: DelayLine,
+    detector: PitchDetector,
+}
+
+impl PitchShifter {
+    pub fn new(sample_rate: f32) -> Self {
+        let grain = GRAIN_MS * 0.001 * sample_rate;
+        Self {
+            mode: PitchMode::Shift,
+            semitones: 0.0,
+            mix: 0.5,
+            key: 0,
+            scale: Scale::Major,
+            steps: 2,
+            a4: 440.0,
+            sample_rate,
+            grain,
+            phase: 0.0,
+            current_semitones: 0.0,
+            line: DelayLine::new(grain as usize + 1),
+            detector: PitchDetector::new(sample_rate),
+        }
+    }
+
+    // Shift amount in semitones and cents for shift mode, drop in semitones
+    // below standard tuning for drop mode.
+    pub fn set_params(&mut self, mode: PitchMode, semitones: i32, cents: f32, drop: i32, mix: f32) {
+        if mode != PitchMode::Harmony {
+            self.semitones = match mode {
+                PitchMode::Drop => -drop as f32,
+                _ => semitones as f32 + cents / 100.0,
+            };
+        }
+        self.mode = mode;
+        self.mix = mix;
+    }
+
+    // Key as a pitch class (0 = C), the harmony interval in scale steps and
+    // the A4 reference shared with the tuner.
+    pub fn set_harmony(&mut self, key: usize, scale: Scale, steps: i32, a4: f32) {
+        self.key = key;
+        self.a4 = a4;
+        self.scale = scale;
+        self.steps = steps;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        // The harmony interval follows the detected note, holding the last
+        // interval through silence and unclear pitch.
+        if self.mode == PitchMode::Harmony
+            && let Some(freq) = self.detector.process(input)
+        {
+            let note = frequency_to_note(freq, self.a4).round() as i32;
+            self.semitones = self.scale.interval(note, self.key, self.steps) as f32;
+        }
+
+        let glide = 1.0 - (-1.0 / (GLIDE_MS * 0.001 * self.sample_rate)).exp();
+        let (dry_gain, wet_gain) = match self.mode {
+            PitchMode::Drop => (0.0, 1.0),
+            _ => (1.0 - self.mix, self.mix),
+        };
+
+        for sample in input.iter_mut() {
+            self.current_semitones += (self.semitones - self.current_semitones) * glide;
+            let ratio = 2.0_f32.powf(self.current_semitones / 12.0);
+
+            // Two read taps half a grain apart sweep through the delay line at
+            // the shifted rate, crossfaded with complementary sine windows.
+            self.line.write(*sample);
+            self.phase = (self.phase + (1.0 - ratio) / self.grain).rem_euclid(1.0);
+            let other = (self.phase + 0.5).fract();
+            let window = (std::f32::consts::PI * self.phase).sin().powi(2);
+            let wet = self.line.read(self.phase * self.grain) * window
+                + self.line.read(other * self.grain) * (1.0 - window);
+
+            *sample = *sample * dry_gain + wet * wet_gain;
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use super::lfo::LfoShape;
+use super::pitch_detector::PitchDetector;
+use super::smoothing::SmoothedValue;
+
+pub struct RingModulator {
+    shape: LfoShape,
+    frequency: f32,
+    fine: f32,
+    lfo_rate: f32,
+    lfo_depth: f32,
+    mix: f32,
+    tracking: bool,
+    tracked: f32,
+    sample_rate: f32,
+    carrier_phase: f32,
+    lfo_phase: f32,
+    carrier_hz: SmoothedValue,
+    detector: PitchDetector,
+}
+
+impl RingModulator {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            shape: LfoShape::Sine,
+            frequency: 440.0,
+            fine: 1.0,
+            lfo_rate: 0.0,
+            lfo_depth: 0.0,
+            mix: 1.0,
+            tracking: false,
+            tracked: 440.0,
+            sample_rate,
+            carrier_phase: 0.0,
+            lfo_phase: 0.0,
+            carrier_hz: SmoothedValue::new(sample_rate, 440.0, 30.0),
+            detector: PitchDetector::new(sample_rate),
+        }
+    }
+
+    // Frequency in Hz, fine in cents. With tracking the detected input pitch replaces the frequency.
+    pub fn set_params(
+        &mut self,
+        shape: LfoShape,
+        frequency: f32,
+        fine: f32,
+        tracking: bool,
+        mix: f32,
+    ) {
+        self.shape = shape;
+        self.frequency = frequency;
+        self.fine = 2.0_f32.powf(fine / 1200.0);
+        self.tracking = tracking;
+        self.mix = mix;
+    }
+
+    // LFO depth 0..1 sweeps the carrier up to an octave either way.
+    pub fn set_lfo(&mut self, rate: f32, depth: f32) {
+        self.lfo_rate = rate;
+        self.lfo_depth = depth;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        let base = if self.tracking {
+            if let Some(pitch) = self.detector.process(input) {
+                self.tracked = pitch;
+            }
+            self.tracked
+        } else {
+            self.frequency
+        };
+        self.carrier_hz.set_target(base * self.fine);
+
+        for sample in input.iter_mut() {
+            let lfo = LfoShape::Sine.value(self.lfo_phase) * self.lfo_depth;
+            self.lfo_phase = (self.lfo_phase + self.lfo_rate / self.sample_rate).fract();
+
+            let carrier_hz = self.carrier_hz.next() * 2.0_f32.powf(lfo);
+            let carrier = self.shape.value(self.carrier_phase);
+            self.carrier_phase = (self.carrier_phase + carrier_hz / self.sample_rate).fract();
+
+            *sample = *sample * (1.0 - self.mix) + *sample * carrier * self.mix;
+        }
+    }
+}
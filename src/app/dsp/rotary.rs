@@ -0,0 +1,118 @@
+use super::delay_line::DelayLine;
+
+const CROSSOVER_HZ: f32 = 800.0;
+
+struct Rotor {
+    slow_hz: f32,
+    fast_hz: f32,
+    // Time constants for spinning up and down, in seconds.
+    accel_time: f32,
+    decel_time: f32,
+    // Doppler delay swing in milliseconds and amplitude modulation depth.
+    doppler_ms: f32,
+    am_depth: f32,
+    speed: f32,
+    angle: f32,
+    line: DelayLine,
+}
+
+impl Rotor {
+    fn new(
+        sample_rate: f32,
+        slow_hz: f32,
+        fast_hz: f32,
+        accel_time: f32,
+        decel_time: f32,
+        doppler_ms: f32,
+        am_depth: f32,
+    ) -> Self {
+        let max_delay = (2.0 * doppler_ms * 0.001 * sample_rate) as usize + 2;
+        Self {
+            slow_hz,
+            fast_hz,
+            accel_time,
+            decel_time,
+            doppler_ms,
+            am_depth,
+            speed: slow_hz,
+            angle: 0.0,
+            line: DelayLine::new(max_delay),
+        }
+    }
+
+    // Advances the rotor by one sample and returns what each mic hears.
+    fn process(
+        &mut self,
+        input: f32,
+        fast: bool,
+        mic_angles: [f32; 2],
+        sample_rate: f32,
+    ) -> [f32; 2] {
+        let target = if fast { self.fast_hz } else { self.slow_hz };
+        let time = if target > self.speed {
+            self.accel_time
+        } else {
+            self.decel_time
+        };
+        self.speed += (target - self.speed) * (1.0 - (-1.0 / (time * sample_rate)).exp());
+        self.angle = (self.angle + self.speed / sample_rate).fract();
+
+        self.line.write(input);
+        let doppler = self.doppler_ms * 0.001 * sample_rate;
+        mic_angles.map(|mic| {
+            let position = std::f32::consts::TAU * (self.angle - mic);
+            let delay = doppler * (1.0 + position.sin());
+            let gain = 1.0 - self.am_depth * 0.5 * (1.0 - position.cos());
+            self.line.read(delay) * gain
+        })
+    }
+}
+
+pub struct Rotary {
+    fast: bool,
+    spread: f32,
+    sample_rate: f32,
+    low: f32,
+    horn: Rotor,
+    drum: Rotor,
+}
+
+impl Rotary {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            fast: false,
+            spread: 0.7,
+            sample_rate,
+            low: 0.0,
+            horn: Rotor::new(sample_rate, 0.8, 6.7, 0.7, 1.0, 0.6, 0.5),
+            drum: Rotor::new(sample_rate, 0.7, 5.8, 4.0, 5.0, 0.3, 0.25),
+        }
+    }
+
+    // Spread 0..1 places the two mics between the same spot and opposite sides.
+    pub fn set_params(&mut self, fast: bool, spread: f32) {
+        self.fast = fast;
+        self.spread = spread;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let dt = 1.0 / self.sample_rate;
+        let crossover_alpha = dt / (1.0 / (std::f32::consts::TAU * CROSSOVER_HZ) + dt);
+        let mic_angles = [-0.25 * self.spread, 0.25 * self.spread];
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let input = 0.5 * (*l + *r);
+            self.low += crossover_alpha * (input - self.low);
+            let high = input - self.low;
+
+            let [horn_l, horn_r] = self
+                .horn
+                .process(high, self.fast, mic_angles, self.sample_rate);
+            let [drum_l, drum_r] =
+                self.drum
+                    .process(self.low, self.fast, mic_angles, self.sample_rate);
+            *l = horn_l + drum_l;
+            *r = horn_r + drum_r;
+        }
+    }
+}
@@ -0,0 +1,343 @@
+// Mutes the output on sustained DC or runaway feedback. Once tripped it
+// stays muted until the user resets it.
+//
+// Every feedback path in the chain is bounded, so a runaway doesn't grow
+// forever; it settles into loud output that keeps going. That is what gets
+// caught, measured on the pre-limiter output in 100 ms windows:
+// - Self-oscillation: the input has sat below INPUT_FLOOR_DB for
+//   UNEXPLAINED_SECONDS, yet the output averaged over that time is above
+//   SUSTAIN_DB and its second half is within HOLD_DB of its first. Averaging
+//   rides over the gaps between echo repeats, and the halves tell a steady
+//   oscillation from a fading tail. Looper playback is excused, since it is
+//   meant to keep going on its own; a delay held at maximum feedback this
+//   loud is not.
+// - Howl: the smoothed output holds above LOUD_DB, never falling HOLD_DB
+//   below its peak, for HOWL_SECONDS while either the input or output is
+//   close to a single sine. Acoustic feedback raises the input along with
+//   the output, but played notes are rich in harmonics and fade.
+// Output that is no longer finite, or far above anything the chain can make
+// from real input, trips straight away.
+const DC_LEVEL: f32 = 0.25;
+const DC_SECONDS: f32 = 0.5;
+const LEVEL_SECONDS: f32 = 0.3;
+const WINDOW_SECONDS: f32 = 0.1;
+const LOUD_DB: f32 = -10.0;
+const HOLD_DB: f32 = 3.0;
+const INPUT_FLOOR_DB: f32 = -45.0;
+// Well below where self-oscillation settles, which depends on the mix
+const SUSTAIN_DB: f32 = -35.0;
+const UNEXPLAINED_SECONDS: f32 = 5.0;
+const UNEXPLAINED_WINDOWS: usize = (UNEXPLAINED_SECONDS / WINDOW_SECONDS) as usize;
+// 1.0 is a pure sine
+const HOWL_PURITY: f32 = 0.9;
+const HOWL_SECONDS: f32 = 2.0;
+const CEILING_DB: f32 = 40.0;
+const FADE_MS: f32 = 10.0;
+
+// Smoothed power of a signal and of its first and second differences. For a
+// sine at w radians per sample each difference scales the power by
+// 2 - 2cos(w); any spread of partials makes the second step the larger one.
+#[derive(Default)]
+struct Meter {
+    power: [f32; 3],
+    last: [f32; 2],
+}
+
+impl Meter {
+    fn push(&mut self, sample: f32, coef: f32) {
+        let first = sample - self.last[0];
+        let second = first - (self.last[0] - self.last[1]);
+        self.last = [sample, self.last[0]];
+        for (power, value) in self.power.iter_mut().zip([sample, first, second]) {
+            *power += (value * value - *power) * coef;
+        }
+    }
+
+    fn db(&self) -> f32 {
+        10.0 * self.power[0].max(1e-12).log10()
+    }
+
+    fn purity(&self) -> f32 {
+        let [plain, first, second] = self.power;
+        first * first / (plain * second).max(1e-20)
+    }
+}
+
+// Windows in a row that a condition has held, and the loudest output seen
+// during them.
+#[derive(Default)]
+struct Run {
+    windows: usize,
+    peak_db: f32,
+}
+
+impl Run {
+    fn update(&mut self, holding: bool, output_db: f32) {
+        if holding && (self.windows == 0 || output_db > self.peak_db - HOLD_DB) {
+            self.peak_db = if self.windows == 0 {
+                output_db
+            } else {
+                self.peak_db.max(output_db)
+            };
+            self.windows += 1;
+        } else {
+            self.windows = 0;
+        }
+    }
+
+    fn seconds(&self) -> f32 {
+        self.windows as f32 * WINDOW_SECONDS
+    }
+}
+
+pub struct SafetyMute {
+    sample_rate: f32,
+    dc: f32,
+    dc_coef: f32,
+    level_coef: f32,
+    input: Meter,
+    output: Meter,
+    window_samples: usize,
+    window_length: usize,
+    window_power: f32,
+    // Mean output power of the most recent windows, oldest at `history_pos`
+    history: [f32; UNEXPLAINED_WINDOWS],
+    history_pos: usize,
+    quiet_windows: usize,
+    howl: Run,
+    dc_samples: usize,
+    gain: f32,
+    fade_step: f32,
+}
+
+impl SafetyMute {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            sample_rate,
+            dc: 0.0,
+            dc_coef: 1.0 - (-1.0 / (0.3 * sample_rate)).exp(),
+            level_coef: 1.0 - (-1.0 / (LEVEL_SECONDS * sample_rate)).exp(),
+            input: Meter::default(),
+            output: Meter::default(),
+            window_samples: 0,
+            window_length: ((WINDOW_SECONDS * sample_rate) as usize).max(1),
+            window_power: 0.0,
+            history: [0.0; UNEXPLAINED_WINDOWS],
+            history_pos: 0,
+            quiet_windows: 0,
+            howl: Run::default(),
+            dc_samples: 0,
+            gain: 1.0,
+            fade_step: 1.0 / (FADE_MS * 0.001 * sample_rate),
+        }
+    }
+
+    // Fed the block's input before any processing, so output the player is
+    // causing isn't mistaken for feedback.
+    pub fn watch_input(&mut self, input: &[f32]) {
+        for &sample in input {
+            self.input.push(sample, self.level_coef);
+        }
+    }
+
+    // `looping` is set while the looper is playing back. Returns true if the
+    // mute tripped during this block.
+    pub fn process(
+        &mut self,
+        left: &mut [f32],
+        right: &mut [f32],
+        muted: bool,
+        looping: bool,
+    ) -> bool {
+        if !muted && self.gain < 1.0 {
+            // The user reset it; start watching from scratch
+            self.dc_samples = 0;
+            self.dc = 0.0;
+            self.output = Meter::default();
+            self.window_power = 0.0;
+            self.quiet_windows = 0;
+            self.howl = Run::default();
+        }
+        let mut tripped = false;
+        let mut muting = muted;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let mono = (*l + *r) * 0.5;
+            self.dc += (mono - self.dc) * self.dc_coef;
+            self.output.push(mono, self.level_coef);
+            self.window_power += mono * mono;
+
+            self.dc_samples = if self.dc.abs() > DC_LEVEL {
+                self.dc_samples + 1
+            } else {
+                0
+            };
+
+            self.window_samples += 1;
+            let runaway = if self.window_samples >= self.window_length {
+                self.window_samples = 0;
+                self.check_runaway(looping)
+            } else {
+                false
+            };
+
+            if !muting && (runaway || self.dc_samples as f32 > DC_SECONDS * self.sample_rate) {
+                muting = true;
+                tripped = true;
+            }
+
+            self.gain = if muting {
+                (self.gain - self.fade_step).max(0.0)
+            } else {
+                (self.gain + self.fade_step).min(1.0)
+            };
+            *l *= self.gain;
+            *r *= self.gain;
+        }
+        tripped
+    }
+
+    fn check_runaway(&mut self, looping: bool) -> bool {
+        if !self.output.power.iter().all(|power| power.is_finite()) {
+            return true;
+        }
+        let output_db = self.output.db();
+        if output_db > CEILING_DB {
+            return true;
+        }
+
+        let pure = self.output.purity().max(self.input.purity()) > HOWL_PURITY;
+        self.howl.update(output_db > LOUD_DB && pure, output_db);
+
+        self.history[self.history_pos] = self.window_power / self.window_length as f32;
+        self.history_pos = (self.history_pos + 1) % UNEXPLAINED_WINDOWS;
+        self.window_power = 0.0;
+        self.quiet_windows = if self.input.db() < INPUT_FLOOR_DB && !looping {
+            self.quiet_windows + 1
+        } else {
+            0
+        };
+
+        self.howl.seconds() >= HOWL_SECONDS
+            || (self.quiet_windows >= UNEXPLAINED_WINDOWS && self.sustained_output())
+    }
+
+    fn sustained_output(&self) -> bool {
+        let half = UNEXPLAINED_WINDOWS / 2;
+        let mean = |age: std::ops::Range<usize>| {
+            let count = age.len() as f32;
+            age.map(|age| self.history[(self.history_pos + age) % UNEXPLAINED_WINDOWS])
+                .sum::<f32>()
+                / count
+        };
+        let older = mean(0..half);
+        let newer = mean(half..UNEXPLAINED_WINDOWS);
+        let db = |power: f32| 10.0 * power.max(1e-12).log10();
+        db((older + newer) * 0.5) > SUSTAIN_DB && db(newer) > db(older) - HOLD_DB
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::dsp::tape_echo::TapeEcho;
+    use std::f32::consts::TAU;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+    const BLOCK: usize = 256;
+
+    // A decaying note with a guitar-like spread of harmonics.
+    fn note(t: f32, every: f32, amplitude: f32) -> f32 {
+        let envelope = (-(t % every) * 1.5).exp() * amplitude;
+        (1..8)
+            .map(|harmonic| (TAU * 110.0 * harmonic as f32 * t).sin() / harmonic as f32)
+            .sum::<f32>()
+            * envelope
+    }
+
+    // Runs `input` through `chain` into the mute and reports whether it tripped.
+    fn trips(
+        seconds: f32,
+        looping: bool,
+        input: impl Fn(f32) -> f32,
+        mut chain: impl FnMut(&mut [f32], &mut [f32]),
+    ) -> bool {
+        let mut safety = SafetyMute::new(SAMPLE_RATE);
+        let blocks = (seconds * SAMPLE_RATE) as usize / BLOCK;
+        (0..blocks).any(|block| {
+            let dry: Vec<f32> = (0..BLOCK)
+                .map(|i| input((block * BLOCK + i) as f32 / SAMPLE_RATE))
+                .collect();
+            let mut left = dry.clone();
+            let mut right = dry.clone();
+            chain(&mut left, &mut right);
+            safety.watch_input(&dry);
+            safety.process(&mut left, &mut right, false, looping)
+        })
+    }
+
+    fn pluck(t: f32) -> f32 {
+        if t < 0.1 { note(t, 10.0, 0.5) } else { 0.0 }
+    }
+
+    #[test]
+    fn self_oscillating_tape_echo_trips() {
+        for time in [50.0, 300.0] {
+            let mut echo = TapeEcho::new(SAMPLE_RATE);
+            echo.set_params(time, 1, 1.1, 0.5);
+            assert!(trips(15.0, false, pluck, |left, right| echo.process(left, right)));
+        }
+    }
+
+    #[test]
+    fn acoustic_howl_trips() {
+        // The howl builds in the input and is driven hard by the amp
+        let howl = |t: f32| (0.02 * 10.0_f32.powf(t)).min(0.4) * (TAU * 1200.0 * t).sin();
+        assert!(trips(10.0, false, howl, |left, right| {
+            for sample in left.iter_mut().chain(right.iter_mut()) {
+                *sample = (*sample * 30.0).tanh();
+            }
+        }));
+    }
+
+    #[test]
+    fn loud_playing_does_not_trip() {
+        let distorted = |left: &mut [f32], right: &mut [f32]| {
+            for sample in left.iter_mut().chain(right.iter_mut()) {
+                *sample = (*sample * 30.0).tanh() * 2.5;
+            }
+        };
+        assert!(!trips(30.0, false, |t| note(t, 1.0, 0.3), distorted));
+        assert!(!trips(30.0, false, |t| note(t, 8.0, 0.3), distorted));
+    }
+
+    #[test]
+    fn fading_echoes_do_not_trip() {
+        let mut echo = TapeEcho::new(SAMPLE_RATE);
+        echo.set_params(400.0, 1, 0.8, 0.6);
+        let phrase = |t: f32| if t < 4.0 { note(t, 1.0, 0.5) } else { 0.0 };
+        assert!(!trips(30.0, false, phrase, |left, right| {
+            echo.process(left, right);
+            for sample in left.iter_mut().chain(right.iter_mut()) {
+                *sample *= 3.0;
+            }
+        }));
+    }
+
+    #[test]
+    fn looper_playback_does_not_trip() {
+        let mut t = 0.0;
+        assert!(!trips(
+            30.0,
+            true,
+            |_| 0.0,
+            |left, right| {
+                for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+                    *l = note(t, 2.0, 0.5);
+                    *r = *l;
+                    t += 1.0 / SAMPLE_RATE;
+                }
+            }
+        ));
+    }
+}
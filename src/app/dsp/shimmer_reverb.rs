@@ -0,0 +1,123 @@
+use super::delay_line::DelayLine;
+use super::pitch_shift::{PitchMode, PitchShifter};
+
+// Mutually prime-ish line lengths in milliseconds for the feedback delay network
+const LINE_MS: [f32; 4] = [29.7, 37.1, 41.1, 43.7];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ShimmerInterval {
+    OctaveUp,
+    Fifth,
+    OctaveDown,
+}
+
+impl ShimmerInterval {
+    pub const ALL: [ShimmerInterval; 3] = [
+        ShimmerInterval::OctaveUp,
+        ShimmerInterval::Fifth,
+        ShimmerInterval::OctaveDown,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ShimmerInterval::OctaveUp => "+12",
+            ShimmerInterval::Fifth => "+7",
+            ShimmerInterval::OctaveDown => "-12",
+        }
+    }
+
+    fn semitones(self) -> i32 {
+        match self {
+            ShimmerInterval::OctaveUp => 12,
+            ShimmerInterval::Fifth => 7,
+            ShimmerInterval::OctaveDown => -12,
+        }
+    }
+}
+
+pub struct ShimmerReverb {
+    amount: f32,
+    decay: f32,
+    tone: f32,
+    mix: f32,
+    sample_rate: f32,
+    lines: [DelayLine; 4],
+    lengths: [f32; 4],
+    damping: [f32; 4],
+    shifter: PitchShifter,
+}
+
+impl ShimmerReverb {
+    pub fn new(sample_rate: f32) -> Self {
+        let lengths = LINE_MS.map(|ms| ms * 0.001 * sample_rate);
+        Self {
+            amount: 0.5,
+            decay: 4.0,
+            tone: 0.5,
+            mix: 0.4,
+            sample_rate,
+            lines: lengths.map(|length| DelayLine::new(length as usize + 1)),
+            lengths,
+            damping: [0.0; 4],
+            shifter: PitchShifter::new(sample_rate),
+        }
+    }
+
+    // Decay is the RT60 in seconds; amount, tone and mix are 0..1.
+    pub fn set_params(
+        &mut self,
+        interval: ShimmerInterval,
+        amount: f32,
+        decay: f32,
+        tone: f32,
+        mix: f32,
+    ) {
+        self.shifter
+            .set_params(PitchMode::Shift, interval.semitones(), 0.0, 0, 1.0);
+        self.amount = amount.clamp(0.0, 1.0);
+        self.decay = decay.max(0.1);
+        self.tone = tone;
+        self.mix = mix;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let gains = self
+            .lengths
+            .map(|length| 10.0_f32.powf(-3.0 * length / (self.decay * self.sample_rate)));
+        let tone_hz = 1000.0 + self.tone * 9000.0;
+        let damping_alpha = 1.0 - (-std::f32::consts::TAU * tone_hz / self.sample_rate).exp();
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let input = (*l + *r) * 0.5;
+
+            let outputs: [f32; 4] = std::array::from_fn(|n| self.lines[n].read(self.lengths[n]));
+
+            // Householder-style 4x4 Hadamard mix keeps the network lossless
+            // apart from the decay gains and damping.
+            let mut mixed = [
+                outputs[0] + outputs[1] + outputs[2] + outputs[3],
+                outputs[0] - outputs[1] + outputs[2] - outputs[3],
+                outputs[0] + outputs[1] - outputs[2] - outputs[3],
+                outputs[0] - outputs[1] - outputs[2] + outputs[3],
+            ]
+            .map(|x| x * 0.5);
+
+            // One recirculating path goes through the pitch shifter, so every
+            // pass climbs another interval while the rest of the tank decays.
+            let mut shifted = mixed[0];
+            self.shifter.process(std::slice::from_mut(&mut shifted));
+            mixed[0] += (shifted - mixed[0]) * self.amount;
+
+            for n in 0..4 {
+                self.damping[n] += damping_alpha * (mixed[n] - self.damping[n]);
+                self.lines[n].write(input + self.damping[n] * gains[n]);
+            }
+
+            let wet_l = (outputs[0] + outputs[2]) * 0.5;
+            let wet_r = (outputs[1] + outputs[3]) * 0.5;
+
+            *l += wet_l * self.mix;
+            *r += wet_r * self.mix;
+        }
+    }
+}
@@ -0,0 +1,26 @@
+// One-pole parameter smoother, so gain changes ramp instead of stepping.
+pub struct SmoothedValue {
+    current: f32,
+    target: f32,
+    coef: f32,
+}
+
+impl SmoothedValue {
+    // Time constant in milliseconds.
+    pub fn new(sample_rate: f32, initial: f32, time_ms: f32) -> Self {
+        Self {
+            current: initial,
+            target: initial,
+            coef: 1.0 - (-1.0 / (time_ms * 0.001 * sample_rate)).exp(),
+        }
+    }
+
+    pub fn set_target(&mut self, target: f32) {
+        self.target = target;
+    }
+
+    pub fn next(&mut self) -> f32 {
+        self.current += (self.target - self.current) * self.coef;
+        self.current
+    }
+}
@@ -0,0 +1,127 @@
+use super::delay_line::DelayLine;
+
+// Cascaded stretched all-passes smear the low end in time, which is what
+// gives a spring its descending "boing" chirp.
+const DISPERSION_STAGES: usize = 60;
+const DISPERSION_COEF: f32 = 0.6;
+const CHIRP_HZ: f32 = 4400.0;
+// Round-trip times of the two springs in the tank, in milliseconds
+const SPRING_MS: [f32; 2] = [37.0, 43.0];
+const LOW_CUT_HZ: f32 = 300.0;
+
+struct StretchedAllpass {
+    inputs: Vec<f32>,
+    outputs: Vec<f32>,
+    pos: usize,
+}
+
+impl StretchedAllpass {
+    fn new(stretch: usize) -> Self {
+        Self {
+            inputs: vec![0.0; stretch],
+            outputs: vec![0.0; stretch],
+            pos: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let output = DISPERSION_COEF * input + self.inputs[self.pos]
+            - DISPERSION_COEF * self.outputs[self.pos];
+        self.inputs[self.pos] = input;
+        self.outputs[self.pos] = output;
+        self.pos = (self.pos + 1) % self.inputs.len();
+        output
+    }
+}
+
+struct Spring {
+    dispersion: Vec<StretchedAllpass>,
+    line: DelayLine,
+    length: f32,
+    damping: f32,
+}
+
+impl Spring {
+    fn new(sample_rate: f32, length_ms: f32) -> Self {
+        let stretch = (sample_rate / (2.0 * CHIRP_HZ)).max(1.0) as usize;
+        let length = length_ms * 0.001 * sample_rate;
+        Self {
+            dispersion: (0..DISPERSION_STAGES)
+                .map(|_| StretchedAllpass::new(stretch))
+                .collect(),
+            line: DelayLine::new(length as usize + 1),
+            length,
+            damping: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let returned = self.line.read(self.length);
+        // Losses along the wire take the top off each pass; each echo comes back
+        // darker and the repeating chirps are heard as drip.
+        self.damping += damping * (returned - self.damping);
+        let mut signal = input + feedback * self.damping;
+        for stage in &mut self.dispersion {
+            signal = stage.process(signal);
+        }
+        self.line.write(signal);
+        returned
+    }
+}
+
+pub struct SpringReverb {
+    dwell: f32,
+    tone: f32,
+    mix: f32,
+    sample_rate: f32,
+    springs: [Spring; 2],
+    low_cut: f32,
+    tone_state: [f32; 2],
+}
+
+impl SpringReverb {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            dwell: 0.5,
+            tone: 0.5,
+            mix: 0.3,
+            sample_rate,
+            springs: SPRING_MS.map(|ms| Spring::new(sample_rate, ms)),
+            low_cut: 0.0,
+            tone_state: [0.0; 2],
+        }
+    }
+
+    // Dwell, tone and mix 0..1. Dwell drives the tank harder and lengthens the decay.
+    pub fn set_params(&mut self, dwell: f32, tone: f32, mix: f32) {
+        self.dwell = dwell;
+        self.tone = tone;
+        self.mix = mix;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let low_cut_alpha = 1.0 - (-std::f32::consts::TAU * LOW_CUT_HZ / self.sample_rate).exp();
+        let tone_hz = 1500.0 + self.tone * 6500.0;
+        let tone_alpha = 1.0 - (-std::f32::consts::TAU * tone_hz / self.sample_rate).exp();
+        let damping = 1.0 - (-std::f32::consts::TAU * 3000.0 / self.sample_rate).exp();
+        let drive = 1.0 + self.dwell * 5.0;
+        let feedback = 0.55 + self.dwell * 0.35;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let input = (*l + *r) * 0.5;
+            // Springs don't carry much bass; the transducer acts like a high-pass
+            self.low_cut += low_cut_alpha * (input - self.low_cut);
+            let driven = ((input - self.low_cut) * drive).tanh() / drive.sqrt();
+
+            for ((sample, spring), tone_state) in [l, r]
+                .into_iter()
+                .zip(&mut self.springs)
+                .zip(&mut self.tone_state)
+            {
+                let wet = spring.process(driven, feedback, damping);
+                *tone_state += tone_alpha * (wet - *tone_state);
+                *sample += *tone_state * self.mix;
+            }
+        }
+    }
+}
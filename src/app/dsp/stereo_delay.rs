@@ -0,0 +1,141 @@
+use super::delay_line::DelayLine;
+use super::smoothing::SmoothedValue;
+
+pub const MAX_TAPS: usize = 8;
+// Long enough for the slowest synced division: a whole note at 40 BPM.
+pub const MAX_TIME_MS: f32 = 6000.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DelayMode {
+    Stereo,
+    PingPong,
+    MultiTap,
+}
+
+impl DelayMode {
+    pub const ALL: [DelayMode; 3] = [DelayMode::Stereo, DelayMode::PingPong, DelayMode::MultiTap];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DelayMode::Stereo => "Stereo",
+            DelayMode::PingPong => "Ping-Pong",
+            DelayMode::MultiTap => "Multi-Tap",
+        }
+    }
+}
+
+pub struct StereoDelay {
+    mode: DelayMode,
+    feedback: f32,
+    mix: f32,
+    tap_count: usize,
+    tap_levels: [f32; MAX_TAPS],
+    tap_pans: [f32; MAX_TAPS],
+    sample_rate: f32,
+    times: [SmoothedValue; 2],
+    tap_times: [SmoothedValue; MAX_TAPS],
+    lines: [DelayLine; 2],
+}
+
+impl StereoDelay {
+    pub fn new(sample_rate: f32) -> Self {
+        let max_delay = (MAX_TIME_MS * 0.001 * sample_rate) as usize;
+        let smoothed = |ms: f32| SmoothedValue::new(sample_rate, ms * 0.001 * sample_rate, 50.0);
+        Self {
+            mode: DelayMode::Stereo,
+            feedback: 0.4,
+            mix: 0.4,
+            tap_count: 4,
+            tap_levels: [1.0; MAX_TAPS],
+            tap_pans: [0.0; MAX_TAPS],
+            sample_rate,
+            times: [smoothed(375.0), smoothed(500.0)],
+            tap_times: std::array::from_fn(|_| smoothed(250.0)),
+            lines: [DelayLine::new(max_delay), DelayLine::new(max_delay)],
+        }
+    }
+
+    // Left and right times in milliseconds.
+    pub fn set_params(
+        &mut self,
+        mode: DelayMode,
+        left_time: f32,
+        right_time: f32,
+        feedback: f32,
+        mix: f32,
+    ) {
+        self.mode = mode;
+        for (smoothed, time) in self.times.iter_mut().zip([left_time, right_time]) {
+            smoothed.set_target(time.clamp(1.0, MAX_TIME_MS) * 0.001 * self.sample_rate);
+        }
+        self.feedback = feedback.clamp(0.0, 0.98);
+        self.mix = mix;
+    }
+
+    // Tap times in milliseconds, pans -1 (left) to 1 (right). Only the first `count` taps sound.
+    pub fn set_taps(
+        &mut self,
+        count: usize,
+        times: &[f32; MAX_TAPS],
+        levels: &[f32; MAX_TAPS],
+        pans: &[f32; MAX_TAPS],
+    ) {
+        self.tap_count = count.clamp(1, MAX_TAPS);
+        for (smoothed, time) in self.tap_times.iter_mut().zip(times) {
+            smoothed.set_target(time.clamp(1.0, MAX_TIME_MS) * 0.001 * self.sample_rate);
+        }
+        self.tap_levels = *levels;
+        self.tap_pans = *pans;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let (wet_l, wet_r) = match self.mode {
+                DelayMode::Stereo => self.process_stereo(*l, *r),
+                DelayMode::PingPong => self.process_ping_pong(*l, *r),
+                DelayMode::MultiTap => self.process_multi_tap(*l, *r),
+            };
+            *l += wet_l * self.mix;
+            *r += wet_r * self.mix;
+        }
+    }
+
+    fn process_stereo(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let mut wet = [0.0; 2];
+        for (i, input) in [left, right].into_iter().enumerate() {
+            let time = self.times[i].next();
+            wet[i] = self.lines[i].read(time);
+            self.lines[i].write(input + self.feedback * wet[i]);
+        }
+        (wet[0], wet[1])
+    }
+
+    // The mono input enters on the left and each repeat crosses to the other side.
+    fn process_ping_pong(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let wet_l = self.lines[0].read(self.times[0].next());
+        let wet_r = self.lines[1].read(self.times[1].next());
+        self.lines[0].write((left + right) * 0.5 + self.feedback * wet_r);
+        self.lines[1].write(wet_l);
+        (wet_l, wet_r)
+    }
+
+    // All taps read one line; the last tap feeds back into it.
+    fn process_multi_tap(&mut self, left: f32, right: f32) -> (f32, f32) {
+        let mut wet = (0.0, 0.0);
+        let mut last = 0.0;
+        for i in 0..MAX_TAPS {
+            // Keep every tap gliding so re-enabling one doesn't jump
+            let time = self.tap_times[i].next();
+            if i >= self.tap_count {
+                continue;
+            }
+            let tap = self.lines[0].read(time) * self.tap_levels[i];
+            let angle = (self.tap_pans[i] + 1.0) * std::f32::consts::FRAC_PI_4;
+            wet.0 += tap * angle.cos();
+            wet.1 += tap * angle.sin();
+            last = tap;
+        }
+        self.lines[0].write((left + right) * 0.5 + self.feedback * last);
+        wet
+    }
+}
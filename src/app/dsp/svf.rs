@@ -0,0 +1,29 @@
+// Topology-preserving state variable filter, stable under fast cutoff sweeps.
+#[derive(Default)]
+pub struct StateVariableFilter {
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+pub struct SvfOutput {
+    pub low: f32,
+    pub band: f32,
+}
+
+impl StateVariableFilter {
+    pub fn process(&mut self, input: f32, cutoff: f32, q: f32, sample_rate: f32) -> SvfOutput {
+        let g = (std::f32::consts::PI * cutoff.min(0.49 * sample_rate) / sample_rate).tan();
+        let k = 1.0 / q.max(0.1);
+        let a1 = 1.0 / (1.0 + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+
+        let v3 = input - self.ic2eq;
+        let v1 = a1 * self.ic1eq + a2 * v3;
+        let v2 = self.ic2eq + a2 * self.ic1eq + a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        SvfOutput { low: v2, band: v1 }
+    }
+}
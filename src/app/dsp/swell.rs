@@ -0,0 +1,92 @@
+use super::envelope::EnvelopeFollower;
+
+// Envelope must drop this far below the threshold before the next onset can retrigger.
+const REARM_RATIO: f32 = 0.5;
+// Once re-armed the gain drops back to silence this quickly, ready for the next swell.
+const FADE_MS: f32 = 10.0;
+
+pub struct Swell {
+    follower: EnvelopeFollower,
+    threshold: f32,
+    step: f32,
+    fade_step: f32,
+    ramp: f32,
+    armed: bool,
+    sample_rate: f32,
+}
+
+impl Swell {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            follower: EnvelopeFollower::new(sample_rate, 0.002, 0.08),
+            threshold: 0.0,
+            step: 0.0,
+            fade_step: 1.0 / (FADE_MS * 0.001 * sample_rate),
+            ramp: 0.0,
+            armed: true,
+            sample_rate,
+        }
+    }
+
+    // Rise time in seconds, sensitivity 0..1 maps the onset threshold from -20 to -60 dB.
+    pub fn set_params(&mut self, rise_time: f32, sensitivity: f32) {
+        let threshold_db = -20.0 - 40.0 * sensitivity.clamp(0.0, 1.0);
+        self.threshold = 10.0_f32.powf(threshold_db / 20.0);
+        self.step = 1.0 / (rise_time.max(0.01) * self.sample_rate);
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        for sample in input.iter_mut() {
+            let envelope = self.follower.process(sample.abs());
+
+            if self.armed && envelope > self.threshold {
+                self.armed = false;
+            } else if envelope < self.threshold * REARM_RATIO {
+                self.armed = true;
+            }
+
+            // An onset rises from wherever the fade got to, so the gain never jumps
+            self.ramp = if self.armed {
+                (self.ramp - self.fade_step).max(0.0)
+            } else {
+                (self.ramp + self.step).min(1.0)
+            };
+            // Squared ramp gives a softer, bowed fade-in than a linear one
+            *sample *= self.ramp * self.ramp;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::TAU;
+
+    const SAMPLE_RATE: f32 = 48000.0;
+
+    #[test]
+    fn onsets_start_from_silence() {
+        // Two 0.5-amplitude notes with a gap long enough to re-arm
+        let note_length = SAMPLE_RATE as usize;
+        let gap = SAMPLE_RATE as usize;
+        let note = |i: usize| 0.5 * (TAU * 110.0 * i as f32 / SAMPLE_RATE).sin();
+        let mut input: Vec<f32> = (0..note_length).map(note).collect();
+        input.extend(std::iter::repeat_n(0.0, gap));
+        input.extend((0..note_length).map(note));
+
+        for sensitivity in [0.0, 0.5, 1.0] {
+            let mut swell = Swell::new(SAMPLE_RATE);
+            swell.set_params(0.5, sensitivity);
+            let mut output = input.clone();
+            swell.process(&mut output);
+
+            for start in [0, note_length + gap] {
+                let onset = &output[start..start + 200];
+                let peak = onset
+                    .iter()
+                    .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+                assert!(peak < 0.01, "sensitivity {sensitivity}: onset peak {peak}");
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use super::delay_line::DelayLine;
+use super::smoothing::SmoothedValue;
+
+const MAX_TIME_MS: f32 = 600.0;
+// Playback heads sit at 1x, 2x and 3x the repeat time, like an RE-201.
+const HEAD_RATIOS: [f32; 3] = [1.0, 2.0, 3.0];
+const WOW_HZ: f32 = 0.6;
+const FLUTTER_HZ: f32 = 7.3;
+
+pub const HEAD_COMBOS: [(&str, [bool; 3]); 7] = [
+    ("Head 1", [true, false, false]),
+    ("Head 2", [false, true, false]),
+    ("Head 3", [false, false, true]),
+    ("Heads 1+2", [true, true, false]),
+    ("Heads 2+3", [false, true, true]),
+    ("Heads 1+3", [true, false, true]),
+    ("Heads 1+2+3", [true, true, true]),
+];
+
+pub struct TapeEcho {
+    heads: [bool; 3],
+    feedback: f32,
+    saturation: f32,
+    tone: f32,
+    wow: f32,
+    flutter: f32,
+    mix: f32,
+    sample_rate: f32,
+    line: DelayLine,
+    // Slow glide on the repeat time so changes bend pitch like a motor speeding up
+    time: SmoothedValue,
+    wow_phase: f32,
+    flutter_phase: f32,
+    tone_state: f32,
+    last_echo: f32,
+}
+
+impl TapeEcho {
+    pub fn new(sample_rate: f32) -> Self {
+        let max_delay = (MAX_TIME_MS * 0.001 * sample_rate * 3.2) as usize;
+        let initial_time = 300.0 * 0.001 * sample_rate;
+        Self {
+            heads: HEAD_COMBOS[0].1,
+            feedback: 0.4,
+            saturation: 0.5,
+            tone: 0.5,
+            wow: 0.3,
+            flutter: 0.3,
+            mix: 0.4,
+            sample_rate,
+            line: DelayLine::new(max_delay),
+            time: SmoothedValue::new(sample_rate, initial_time, 250.0),
+            wow_phase: 0.0,
+            flutter_phase: 0.0,
+            tone_state: 0.0,
+            last_echo: 0.0,
+        }
+    }
+
+    // Repeat time in milliseconds, heads is an index into HEAD_COMBOS.
+    pub fn set_params(&mut self, time: f32, heads: usize, feedback: f32, mix: f32) {
+        let time = time.clamp(20.0, MAX_TIME_MS);
+        self.time.set_target(time * 0.001 * self.sample_rate);
+        self.heads = HEAD_COMBOS[heads.min(HEAD_COMBOS.len() - 1)].1;
+        self.feedback = feedback.clamp(0.0, 1.1);
+        self.mix = mix;
+    }
+
+    // Tape character controls, all 0..1.
+    pub fn set_tape(&mut self, saturation: f32, tone: f32, wow: f32, flutter: f32) {
+        self.saturation = saturation;
+        self.tone = tone;
+        self.wow = wow;
+        self.flutter = flutter;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        // Tone sets the loop lowpass; every pass through it darkens the repeats further
+        let cutoff = 1500.0 + self.tone * 6000.0;
+        let alpha = 1.0 - (-std::f32::consts::TAU * cutoff / self.sample_rate).exp();
+        let drive = 1.0 + self.saturation * 4.0;
+        let active = self.heads.iter().filter(|&&on| on).count().max(1) as f32;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let input = (*l + *r) * 0.5;
+
+            let wow = (std::f32::consts::TAU * self.wow_phase).sin() * self.wow * 0.004;
+            let flutter =
+                (std::f32::consts::TAU * self.flutter_phase).sin() * self.flutter * 0.0015;
+            self.wow_phase = (self.wow_phase + WOW_HZ / self.sample_rate).fract();
+            self.flutter_phase = (self.flutter_phase + FLUTTER_HZ / self.sample_rate).fract();
+            let time = self.time.next() * (1.0 + wow + flutter);
+
+            let mut echo = 0.0;
+            for (&on, ratio) in self.heads.iter().zip(HEAD_RATIOS) {
+                if on {
+                    echo += self.line.read(time * ratio);
+                }
+            }
+            echo /= active.sqrt();
+
+            self.tone_state += alpha * (echo - self.tone_state);
+            self.last_echo = self.tone_state;
+
+            // Record head saturation keeps high feedback settings from running away
+            let record = input + self.feedback * self.last_echo;
+            self.line.write((record * drive).tanh() / drive);
+
+            *l += self.last_echo * self.mix;
+            *r += self.last_echo * self.mix;
+        }
+    }
+}
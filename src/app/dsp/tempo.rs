@@ -0,0 +1,19 @@
+// Note divisions offered for tempo sync, as (label, length in beats).
+pub const DIVISIONS: [(&str, f32); 7] = [
+    ("1/1", 4.0),
+    ("1/2", 2.0),
+    ("1/4", 1.0),
+    ("1/8 dotted", 0.75),
+    ("1/8", 0.5),
+    ("1/8 triplet", 1.0 / 3.0),
+    ("1/16", 0.25),
+];
+
+pub fn division_hz(bpm: f32, division: usize) -> f32 {
+    let beats = DIVISIONS[division.min(DIVISIONS.len() - 1)].1;
+    bpm / 60.0 / beats
+}
+
+pub fn division_ms(bpm: f32, division: usize) -> f32 {
+    1000.0 / division_hz(bpm, division)
+}
@@ -0,0 +1,84 @@
+use super::lfo::LfoShape;
+
+const CROSSOVER_HZ: f32 = 800.0;
+const SMOOTHING_MS: f32 = 2.0;
+
+pub struct Tremolo {
+    shape: LfoShape,
+    rate: f32,
+    depth: f32,
+    pan: bool,
+    harmonic: bool,
+    sample_rate: f32,
+    phase: f32,
+    // Smoothed gains for the in-phase and antiphase LFO, so square waves don't click.
+    gains: [f32; 2],
+    lows: [f32; 2],
+}
+
+impl Tremolo {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            shape: LfoShape::Sine,
+            rate: 5.0,
+            depth: 0.5,
+            pan: false,
+            harmonic: false,
+            sample_rate,
+            phase: 0.0,
+            gains: [1.0; 2],
+            lows: [0.0; 2],
+        }
+    }
+
+    // Rate in Hz, depth 0..1. Pan mode moves the two channels in antiphase,
+    // harmonic mode does the same to the low and high bands.
+    pub fn set_params(
+        &mut self,
+        shape: LfoShape,
+        rate: f32,
+        depth: f32,
+        pan: bool,
+        harmonic: bool,
+    ) {
+        self.shape = shape;
+        self.rate = rate;
+        self.depth = depth;
+        self.pan = pan;
+        self.harmonic = harmonic;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let phase_step = self.rate / self.sample_rate;
+        let smoothing = 1.0 - (-1.0 / (SMOOTHING_MS * 0.001 * self.sample_rate)).exp();
+        let dt = 1.0 / self.sample_rate;
+        let crossover_alpha = dt / (1.0 / (std::f32::consts::TAU * CROSSOVER_HZ) + dt);
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let lfo = self.shape.value(self.phase);
+            for (gain, polarity) in self.gains.iter_mut().zip([1.0, -1.0]) {
+                let target = 1.0 - self.depth * 0.5 * (1.0 - polarity * lfo);
+                *gain += (target - *gain) * smoothing;
+            }
+            let [in_phase, anti_phase] = self.gains;
+
+            for (channel, (sample, low)) in [l, r].into_iter().zip(&mut self.lows).enumerate() {
+                let (a, b) = if self.pan && channel == 1 {
+                    (anti_phase, in_phase)
+                } else {
+                    (in_phase, anti_phase)
+                };
+
+                if self.harmonic {
+                    *low += crossover_alpha * (*sample - *low);
+                    let high = *sample - *low;
+                    *sample = *low * a + high * b;
+                } else {
+                    *sample *= a;
+                }
+            }
+
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
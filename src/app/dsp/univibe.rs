@@ -0,0 +1,83 @@
+use super::phaser::AllPass;
+
+// Resting corner frequencies of the four phase-shift stages. The original
+// circuit uses mismatched capacitors, which is what gives the throbbing sound.
+const STAGE_FREQS: [f32; 4] = [60.0, 240.0, 750.0, 2200.0];
+const SWEEP_OCTAVES: f32 = 2.5;
+// The lamp heats up faster than it cools down.
+const LAMP_ATTACK_MS: f32 = 8.0;
+const LAMP_RELEASE_MS: f32 = 35.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VibeMode {
+    Chorus,
+    Vibrato,
+}
+
+pub struct UniVibe {
+    rate: f32,
+    depth: f32,
+    mode: VibeMode,
+    sample_rate: f32,
+    phase: f32,
+    lamp: f32,
+    stages: [[AllPass; 4]; 2],
+}
+
+impl UniVibe {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            rate: 2.0,
+            depth: 0.7,
+            mode: VibeMode::Chorus,
+            sample_rate,
+            phase: 0.0,
+            lamp: 0.0,
+            stages: [[AllPass::default(); 4]; 2],
+        }
+    }
+
+    // Rate in Hz, depth 0..1.
+    pub fn set_params(&mut self, rate: f32, depth: f32, mode: VibeMode) {
+        self.rate = rate;
+        self.depth = depth;
+        self.mode = mode;
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let phase_step = self.rate / self.sample_rate;
+        let attack = 1.0 - (-1.0 / (LAMP_ATTACK_MS * 0.001 * self.sample_rate)).exp();
+        let release = 1.0 - (-1.0 / (LAMP_RELEASE_MS * 0.001 * self.sample_rate)).exp();
+        let nyquist = 0.49 * self.sample_rate;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            // The lamp brightness follows a squared sine through an asymmetric
+            // lag, giving the skewed, pulsing sweep of the photocell drive.
+            let drive = 0.5 * (1.0 + (std::f32::consts::TAU * self.phase).sin());
+            let target = drive * drive;
+            let coef = if target > self.lamp { attack } else { release };
+            self.lamp += (target - self.lamp) * coef;
+
+            let mut coefs = [0.0; 4];
+            for (coef, base) in coefs.iter_mut().zip(STAGE_FREQS) {
+                let freq =
+                    (base * 2.0_f32.powf(self.depth * SWEEP_OCTAVES * self.lamp)).min(nyquist);
+                let t = (std::f32::consts::PI * freq / self.sample_rate).tan();
+                *coef = (t - 1.0) / (t + 1.0);
+            }
+
+            for (sample, stages) in [l, r].into_iter().zip(&mut self.stages) {
+                let mut wet = *sample;
+                for (stage, coef) in stages.iter_mut().zip(coefs) {
+                    wet = stage.process(wet, coef);
+                }
+                *sample = match self.mode {
+                    VibeMode::Chorus => 0.5 * (*sample + wet),
+                    VibeMode::Vibrato => wet,
+                };
+            }
+
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
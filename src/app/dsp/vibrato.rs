@@ -0,0 +1,48 @@
+use super::delay_line::DelayLine;
+
+const MAX_DEPTH_MS: f32 = 10.0;
+
+pub struct Vibrato {
+    rate: f32,
+    depth: f32,
+    sample_rate: f32,
+    phase: f32,
+    lines: [DelayLine; 2],
+}
+
+impl Vibrato {
+    pub fn new(sample_rate: f32) -> Self {
+        let max_delay = (2.0 * MAX_DEPTH_MS * 0.001 * sample_rate) as usize + 1;
+        Self {
+            rate: 5.0,
+            depth: 1.0,
+            sample_rate,
+            phase: 0.0,
+            lines: [DelayLine::new(max_delay), DelayLine::new(max_delay)],
+        }
+    }
+
+    // Rate in Hz, depth in milliseconds of delay swing.
+    pub fn set_params(&mut self, rate: f32, depth: f32) {
+        self.rate = rate;
+        self.depth = depth.clamp(0.0, MAX_DEPTH_MS);
+    }
+
+    pub fn process(&mut self, left: &mut [f32], right: &mut [f32]) {
+        let ms_to_samples = 0.001 * self.sample_rate;
+        let phase_step = self.rate / self.sample_rate;
+
+        for (l, r) in left.iter_mut().zip(right.iter_mut()) {
+            let lfo = (std::f32::consts::TAU * self.phase).sin();
+            // Fully wet, so only the pitch wobble of the moving tap is heard.
+            let delay = self.depth * (1.0 + lfo) * ms_to_samples;
+
+            for (sample, line) in [l, r].into_iter().zip(&mut self.lines) {
+                line.write(*sample);
+                *sample = line.read(delay);
+            }
+
+            self.phase = (self.phase + phase_step).fract();
+        }
+    }
+}
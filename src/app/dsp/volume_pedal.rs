@@ -0,0 +1,41 @@
+use super::smoothing::SmoothedValue;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Taper {
+    Linear,
+    Audio,
+}
+
+impl Taper {
+    fn apply(self, position: f32) -> f32 {
+        match self {
+            Taper::Linear => position,
+            // 40 dB logarithmic sweep, like an audio-taper pot
+            Taper::Audio => (10.0_f32.powf(2.0 * position) - 1.0) / 99.0,
+        }
+    }
+}
+
+pub struct VolumePedal {
+    gain: SmoothedValue,
+}
+
+impl VolumePedal {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            gain: SmoothedValue::new(sample_rate, 1.0, 20.0),
+        }
+    }
+
+    // Position and minimum volume 0..1; heel-down never drops below the minimum.
+    pub fn set_params(&mut self, position: f32, taper: Taper, minimum: f32) {
+        let position = taper.apply(position.clamp(0.0, 1.0));
+        self.gain.set_target(minimum + (1.0 - minimum) * position);
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        for sample in input.iter_mut() {
+            *sample *= self.gain.next();
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use super::svf::StateVariableFilter;
+
+const SMOOTHING_MS: f32 = 10.0;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WahRange {
+    CryBaby,
+    Vox,
+    Bass,
+}
+
+impl WahRange {
+    pub const ALL: [WahRange; 3] = [WahRange::CryBaby, WahRange::Vox, WahRange::Bass];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            WahRange::CryBaby => "Cry Baby",
+            WahRange::Vox => "Vox",
+            WahRange::Bass => "Bass",
+        }
+    }
+
+    // Heel-down and toe-down resonant frequencies in Hz.
+    fn limits(self) -> (f32, f32) {
+        match self {
+            WahRange::CryBaby => (350.0, 2200.0),
+            WahRange::Vox => (450.0, 1600.0),
+            WahRange::Bass => (150.0, 1000.0),
+        }
+    }
+}
+
+pub struct Wah {
+    position: f32,
+    range: WahRange,
+    q: f32,
+    sample_rate: f32,
+    smoothed_position: f32,
+    filter: StateVariableFilter,
+}
+
+impl Wah {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            position: 0.5,
+            range: WahRange::CryBaby,
+            q: 5.0,
+            sample_rate,
+            smoothed_position: 0.5,
+            filter: StateVariableFilter::default(),
+        }
+    }
+
+    // Position 0..1 from heel to toe.
+    pub fn set_params(&mut self, position: f32, range: WahRange, q: f32) {
+        self.position = position.clamp(0.0, 1.0);
+        self.range = range;
+        self.q = q;
+    }
+
+    pub fn process(&mut self, input: &mut [f32]) {
+        let smoothing = 1.0 - (-1.0 / (SMOOTHING_MS * 0.001 * self.sample_rate)).exp();
+        let (low, high) = self.range.limits();
+
+        for sample in input.iter_mut() {
+            self.smoothed_position += (self.position - self.smoothed_position) * smoothing;
+
+            // The pot taper and the inductor bunch the sweep towards the heel,
+            // and the inductor's losses make the resonance sharper down there.
+            let sweep = self.smoothed_position.powf(1.5);
+            let freq = low * (high / low).powf(sweep);
+            let q = self.q * (1.3 - 0.5 * sweep);
+
+            let output = self.filter.process(*sample, freq, q, self.sample_rate);
+            *sample = 2.0 * output.band / q;
+        }
+    }
+}
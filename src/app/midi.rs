@@ -0,0 +1,69 @@
+use crate::GooseDsp;
+use midir::MidiInput;
+use std::sync::Arc;
+
+const CONTROL_CHANGE: u8 = 0xB0;
+
+impl GooseDsp {
+    pub fn refresh_midi_ports(&mut self) {
+        self.midi_ports = match MidiInput::new("Goose DSP") {
+            Ok(midi_in) => midi_in
+                .ports()
+                .iter()
+                .filter_map(|port| midi_in.port_name(port).ok())
+                .collect(),
+            Err(e) => {
+                eprintln!("Error enumerating MIDI ports: {}", e);
+                Vec::new()
+            }
+        };
+    }
+
+    pub fn connect_midi(&mut self) {
+        self.midi_connection = None;
+
+        let Some(port_name) = self.selected_midi_port.clone() else {
+            return;
+        };
+
+        let midi_in = match MidiInput::new("Goose DSP") {
+            Ok(midi_in) => midi_in,
+            Err(err) => {
+                self.error_message = Some(format!("Failed to open MIDI input: {}", err));
+                return;
+            }
+        };
+
+        let Some(port) = midi_in
+            .ports()
+            .into_iter()
+            .find(|port| midi_in.port_name(port).is_ok_and(|name| name == port_name))
+        else {
+            self.error_message = Some("Selected MIDI port not found".to_string());
+            return;
+        };
+
+        let audio_params = Arc::clone(&self.audio_params);
+        let connection = midi_in.connect(
+            &port,
+            "goose-dsp-input",
+            move |_, message, _| {
+                if let [status, controller, value] = *message
+                    && status & 0xF0 == CONTROL_CHANGE
+                    && let Ok(mut params) = audio_params.lock()
+                    && controller == params.wah_cc
+                {
+                    params.wah_position = value as f32 / 127.0;
+                }
+            },
+            (),
+        );
+
+        match connection {
+            Ok(connection) => self.midi_connection = Some(connection),
+            Err(err) => {
+                self.error_message = Some(format!("Failed to connect MIDI port: {}", err));
+            }
+        }
+    }
+}
@@ -1,6 +1,5 @@
 use serde::{Deserialize, Serialize};
 use std::io::Write;
-use toml;
 
 use crate::GooseDsp;
 
@@ -1,12 +1,12 @@
-use crate::app::dsp;
 use crate::GooseDsp;
+use crate::app::dsp;
+use crate::app::dsp::pitch_detector::PitchDetector;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::{Arc, Mutex};
 
 impl GooseDsp {
     pub fn get_output_level(&self) -> f32 {
-        let level = *self.output_level.lock().unwrap();
-        level
+        *self.output_level.lock().unwrap()
     }
 
     pub fn set_stream(&mut self) {
@@ -63,6 +63,16 @@ impl GooseDsp {
         let stream_config = Arc::clone(&self.stream_config);
         let selected_channel = self.selected_input_channel;
         let audio_params = Arc::clone(&self.audio_params);
+        self.looper
+            .lock()
+            .unwrap()
+            .set_sample_rate(config.sample_rate.0 as f32);
+        self.update_convolution_kernel();
+        self.update_nam_processor();
+        let mut processors =
+            dsp::Processors::new(config.sample_rate.0 as f32, Arc::clone(&self.looper));
+        let mut tuner = PitchDetector::new(config.sample_rate.0 as f32);
+        let tuner_pitch = Arc::clone(&self.tuner_pitch);
 
         let processed_audio = Arc::new(Mutex::new(Vec::new()));
         let processed_audio_clone = Arc::clone(&processed_audio);
@@ -76,7 +86,16 @@ impl GooseDsp {
                     .map(|chunk| chunk[selected_channel])
                     .collect();
 
-                let processed = dsp::process_audio(&channel_data, &config, &audio_params);
+                if audio_params.lock().unwrap().tuner_enabled {
+                    let raw: Vec<f32> = channel_data
+                        .iter()
+                        .map(|&x| x as f32 / i32::MAX as f32)
+                        .collect();
+                    *tuner_pitch.lock().unwrap() = tuner.process(&raw);
+                }
+
+                let processed =
+                    dsp::process_audio(&channel_data, &config, &audio_params, &mut processors);
                 *processed_audio.lock().unwrap() = processed;
             },
             move |err| eprintln!("Input error: {}", err),
@@ -98,17 +117,20 @@ impl GooseDsp {
                     let mut sum_squares = 0.0;
                     let mut peak = 0;
 
+                    let frames = processed.len() / 2;
                     for (i, chunk) in data.chunks_mut(2).enumerate() {
-                        let sample = processed[i % processed.len()];
-                        chunk[0] = sample; // Left channel
-                        chunk[1] = sample; // Right channel
+                        let frame = 2 * (i % frames);
+                        chunk[0] = processed[frame]; // Left channel
+                        chunk[1] = processed[frame + 1]; // Right channel
 
-                        let abs_sample = sample.abs();
-                        if abs_sample > peak {
-                            peak = abs_sample;
-                        }
+                        for &sample in chunk.iter() {
+                            let abs_sample = sample.abs();
+                            if abs_sample > peak {
+                                peak = abs_sample;
+                            }
 
-                        sum_squares += (sample as f32).powi(2);
+                            sum_squares += (sample as f32).powi(2);
+                        }
                     }
 
                     let rms = (sum_squares / processed.len() as f32).sqrt();
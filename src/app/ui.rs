@@ -1,6 +1,24 @@
 use crate::GooseDsp;
+use crate::app::dsp::convolution::{
+    ConvolutionKernel, ConvolutionReverb, ImpulseResponse, MAX_PRE_DELAY_MS,
+};
+use crate::app::dsp::envelope_filter::FilterMode;
+use crate::app::dsp::lfo::LfoShape;
+use crate::app::dsp::looper::{self, LooperSlot, LooperState};
+use crate::app::dsp::nam::{NamModel, NamProcessor};
+use crate::app::dsp::phaser::STAGE_OPTIONS;
+use crate::app::dsp::pitch_detector::{NOTE_NAMES, frequency_to_note};
+use crate::app::dsp::pitch_shift::{HARMONY_INTERVALS, PitchMode, Scale};
+use crate::app::dsp::shimmer_reverb::ShimmerInterval;
+use crate::app::dsp::stereo_delay::{self, DelayMode, MAX_TAPS};
+use crate::app::dsp::tape_echo::HEAD_COMBOS;
+use crate::app::dsp::tempo::DIVISIONS;
+use crate::app::dsp::univibe::VibeMode;
+use crate::app::dsp::volume_pedal::Taper;
+use crate::app::dsp::wah::WahRange;
 use eframe::egui::{self, Painter, Rect, Rgba, Stroke, ThemePreference, Visuals};
 use egui_knob::{self, Knob};
+use std::sync::Arc;
 
 impl GooseDsp {
     pub fn update_ui(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -14,6 +32,13 @@ impl GooseDsp {
 
         self.show_titlebar(ctx, panel_frame);
 
+        if self.rotary_enabled
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.key_pressed(egui::Key::S))
+        {
+            self.set_rotary_fast(!self.rotary_fast);
+        }
+
         if self.show_about {
             self.show_about_window(ctx);
         }
@@ -39,6 +64,22 @@ impl GooseDsp {
                     self.volume_ui(ui);
                 });
 
+                if self.looper_enabled {
+                    ui.heading("Looper");
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        self.looper_ui(ui);
+                    });
+                }
+
+                if self.tuner_enabled {
+                    ui.heading("Tuner");
+                    ui.group(|ui| {
+                        ui.set_width(ui.available_width());
+                        self.tuner_ui(ui);
+                    });
+                }
+
                 ui.heading("Effects");
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     ui.set_width(ui.available_width());
@@ -55,7 +96,12 @@ impl GooseDsp {
         let color = Rgba::from_rgb(0.1 + level * 0.9, 0.8 - level * 0.5, 0.2);
         painter.rect_filled(filled_rect, 2.0, color);
 
-        painter.rect_stroke(rect, 0.0, Stroke::new(1.5, Rgba::from_black_alpha(0.5)), egui::StrokeKind::Outside);
+        painter.rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(1.5, Rgba::from_black_alpha(0.5)),
+            egui::StrokeKind::Outside,
+        );
     }
 
     pub fn show_about_window(&mut self, ctx: &egui::Context) {
@@ -96,12 +142,12 @@ impl GooseDsp {
                         )
                         .clicked()
                     {
-                        ctx.open_url(egui::output::OpenUrl{
+                        ctx.open_url(egui::output::OpenUrl {
                             url: "mailto:dan@binarygoose.dev".to_string(),
-                            new_tab: false});
-                        }
+                            new_tab: false,
+                        });
                     }
-                );
+                });
                 ui.separator();
 
                 if ui.button("Close").clicked() {
@@ -219,10 +265,9 @@ impl GooseDsp {
                     .with_label("In", egui_knob::LabelPosition::Bottom),
                 )
                 .changed()
+                && let Ok(mut params) = self.audio_params.lock()
             {
-                if let Ok(mut params) = self.audio_params.lock() {
-                    params.input_volume = self.input_volume;
-                }
+                params.input_volume = self.input_volume;
             }
 
             if ui
@@ -237,22 +282,340 @@ impl GooseDsp {
                     .with_label("Out", egui_knob::LabelPosition::Bottom),
                 )
                 .changed()
+                && let Ok(mut params) = self.audio_params.lock()
             {
-                if let Ok(mut params) = self.audio_params.lock() {
-                    params.output_volume = self.output_volume;
+                params.output_volume = self.output_volume;
+            }
+
+            if ui.checkbox(&mut self.tuner_enabled, "Tuner").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tuner_enabled = self.tuner_enabled;
+                *self.tuner_pitch.lock().unwrap() = None;
+            }
+            if ui.checkbox(&mut self.looper_enabled, "Looper").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.looper_enabled = self.looper_enabled;
+            }
+
+            if ui.checkbox(&mut self.limiter_enabled, "Limiter").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.limiter_enabled = self.limiter_enabled;
+            }
+            if self.limiter_enabled {
+                if effect_knob(ui, &mut self.limiter_ceiling, -12.0, 0.0, "Ceiling")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.limiter_ceiling = self.limiter_ceiling;
+                }
+                if effect_knob(ui, &mut self.limiter_release, 10.0, 1000.0, "Release")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.limiter_release = self.limiter_release;
+                }
+            }
+        });
+
+        // The audio thread trips this on DC or runaway feedback
+        if let Ok(mut params) = self.audio_params.lock()
+            && params.safety_muted
+        {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    "Output muted: DC offset or runaway feedback detected",
+                );
+                if ui.button("Unmute").clicked() {
+                    params.safety_muted = false;
                 }
+            });
+        }
+    }
+
+    fn looper_ui(&mut self, ui: &mut egui::Ui) {
+        // Snapshot the transport so the lock is only held for each action
+        let (state, length, position, can_undo, can_redo) = {
+            let looper = self.looper.lock().unwrap();
+            (
+                looper.state(),
+                looper.length_secs(),
+                looper.position_secs(),
+                looper.can_undo(),
+                looper.can_redo(),
+            )
+        };
+        let mut export = None;
+
+        ui.horizontal(|ui| {
+            let record_label = match state {
+                LooperState::Empty => "⏺ Rec",
+                LooperState::Recording => "⏺ Close",
+                _ => "⏺ Dub",
+            };
+            if ui.button(record_label).clicked() {
+                let mut buffer = looper::spare_buffer(&self.looper);
+                self.looper.lock().unwrap().record(&mut buffer);
+            }
+            if ui.button("▶ Play").clicked() {
+                self.looper.lock().unwrap().play();
+            }
+            if ui.button("⏹ Stop").clicked() {
+                self.looper.lock().unwrap().stop();
+            }
+            if ui
+                .add_enabled(can_undo, egui::Button::new("Undo"))
+                .clicked()
+            {
+                self.looper.lock().unwrap().undo();
+            }
+            if ui
+                .add_enabled(can_redo, egui::Button::new("Redo"))
+                .clicked()
+            {
+                self.looper.lock().unwrap().redo();
+            }
+            if ui.button("Clear").clicked() {
+                self.looper.lock().unwrap().clear();
+            }
+            if ui
+                .add_enabled(
+                    !matches!(state, LooperState::Empty | LooperState::Recording),
+                    egui::Button::new("Export..."),
+                )
+                .clicked()
+            {
+                export = looper::mixdown(&self.looper);
+            }
+        });
+
+        if let Some((frames, sample_rate)) = export
+            && let Some(path) = rfd::FileDialog::new()
+                .add_filter("WAV audio", &["wav"])
+                .set_file_name("loop.wav")
+                .save_file()
+            && let Err(err) = looper::export_wav(&path, &frames, sample_rate)
+        {
+            self.error_message = Some(err);
+        }
+
+        let progress = match state {
+            LooperState::Recording => position / self.looper_max_length,
+            _ if length > 0.0 => position / length,
+            _ => 0.0,
+        };
+        ui.add(egui::ProgressBar::new(progress).text(format!(
+            "{}  {:.1} / {:.1} s",
+            state.name(),
+            position,
+            length
+        )));
+
+        ui.horizontal(|ui| {
+            let previous_slot = self.looper_slot;
+            egui::ComboBox::from_id_salt("looper_slot")
+                .selected_text(self.looper_slot.name())
+                .show_ui(ui, |ui| {
+                    for slot in LooperSlot::ALL {
+                        ui.selectable_value(&mut self.looper_slot, slot, slot.name());
+                    }
+                });
+            if self.looper_slot != previous_slot
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.looper_slot = self.looper_slot;
+            }
+
+            ui.label("Max length:");
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.looper_max_length)
+                        .range(5.0..=300.0)
+                        .suffix(" s"),
+                )
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.looper_max_length = self.looper_max_length;
+            }
+            if ui
+                .checkbox(&mut self.looper_half_speed, "Half speed")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.looper_half_speed = self.looper_half_speed;
+            }
+            if ui.checkbox(&mut self.looper_reverse, "Reverse").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.looper_reverse = self.looper_reverse;
+            }
+        });
+    }
+
+    fn tuner_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("A4:");
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.tuner_a4)
+                        .range(415.0..=466.0)
+                        .speed(0.1)
+                        .suffix(" Hz"),
+                )
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tuner_a4 = self.tuner_a4;
+            }
+            if ui.checkbox(&mut self.tuner_mute, "Mute output").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tuner_mute = self.tuner_mute;
+            }
+            ui.selectable_value(&mut self.tuner_strobe, false, "Needle");
+            ui.selectable_value(&mut self.tuner_strobe, true, "Strobe");
+        });
+
+        let pitch = *self.tuner_pitch.lock().unwrap();
+        let reading = pitch.map(|freq| {
+            let note = frequency_to_note(freq, self.tuner_a4);
+            let nearest = note.round();
+            (nearest as i32, (note - nearest) * 100.0, freq)
+        });
+
+        ui.horizontal(|ui| match reading {
+            Some((note, cents, freq)) => {
+                ui.heading(format!(
+                    "{}{}",
+                    NOTE_NAMES[note.rem_euclid(12) as usize],
+                    note.div_euclid(12) - 1
+                ));
+                ui.label(format!("{:+.1} cents ({:.1} Hz)", cents, freq));
+            }
+            None => {
+                ui.heading("--");
             }
         });
+
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 30.0), egui::Sense::hover());
+        let cents = reading.map(|(_, cents, _)| cents);
+        if self.tuner_strobe {
+            let dt = ui.ctx().input(|i| i.stable_dt);
+            self.tuner_strobe_phase =
+                (self.tuner_strobe_phase + 0.1 * cents.unwrap_or(0.0) * dt).rem_euclid(1.0);
+            self.draw_tuner_strobe(ui.painter(), rect, cents.is_some());
+        } else {
+            self.draw_tuner_needle(ui.painter(), rect, cents);
+        }
+    }
+
+    fn draw_tuner_needle(&self, painter: &Painter, rect: Rect, cents: Option<f32>) {
+        painter.rect_filled(rect, 2.0, Rgba::from_black_alpha(0.2));
+
+        for step in -5..=5 {
+            let x = rect.center().x + rect.width() * 0.5 * step as f32 / 5.0;
+            let height = if step == 0 { 1.0 } else { 0.4 };
+            painter.line_segment(
+                [
+                    egui::pos2(x, rect.max.y - rect.height() * height),
+                    egui::pos2(x, rect.max.y),
+                ],
+                Stroke::new(1.0, Rgba::from_black_alpha(0.5)),
+            );
+        }
+
+        if let Some(cents) = cents {
+            let x = rect.center().x + rect.width() * 0.5 * (cents / 50.0).clamp(-1.0, 1.0);
+            let color = if cents.abs() < 5.0 {
+                Rgba::from_rgb(0.1, 0.8, 0.2)
+            } else {
+                Rgba::from_rgb(0.9, 0.3, 0.2)
+            };
+            painter.line_segment(
+                [egui::pos2(x, rect.min.y), egui::pos2(x, rect.max.y)],
+                Stroke::new(3.0, color),
+            );
+        }
+    }
+
+    fn draw_tuner_strobe(&self, painter: &Painter, rect: Rect, active: bool) {
+        painter.rect_filled(rect, 2.0, Rgba::from_black_alpha(0.2));
+        if !active {
+            return;
+        }
+
+        // The bands drift with the cents deviation and stand still when in tune
+        let band_width = 20.0;
+        let offset = self.tuner_strobe_phase * 2.0 * band_width;
+        let painter = painter.with_clip_rect(rect);
+        let mut x = rect.min.x - 2.0 * band_width + offset;
+        while x < rect.max.x {
+            let band = Rect::from_min_max(
+                egui::pos2(x, rect.min.y),
+                egui::pos2(x + band_width, rect.max.y),
+            );
+            painter.rect_filled(band, 0.0, Rgba::from_rgb(0.1, 0.8, 0.2));
+            x += 2.0 * band_width;
+        }
     }
 
     fn effects_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Tempo:");
+            if ui
+                .add(
+                    egui::DragValue::new(&mut self.tempo)
+                        .range(40.0..=240.0)
+                        .suffix(" BPM"),
+                )
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tempo = self.tempo;
+            }
+        });
+        ui.add_space(5.0);
+
+        if ui
+            .checkbox(&mut self.boost_enabled, "Clean Boost")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.boost_enabled = self.boost_enabled;
+        }
+        if self.boost_enabled {
+            ui.horizontal(|ui| {
+                if effect_knob(ui, &mut self.boost_gain, 0.0, 20.0, "Gain")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.boost_gain = self.boost_gain;
+                }
+                if ui.checkbox(&mut self.boost_tight, "Tight").changed()
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.boost_tight = self.boost_tight;
+                }
+                if ui.checkbox(&mut self.boost_post, "Post-drive").changed()
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.boost_post = self.boost_post;
+                }
+            });
+            ui.add_space(15.0);
+        }
+
         if ui
             .checkbox(&mut self.overdrive_enabled, "Overdrive")
             .changed()
+            && let Ok(mut params) = self.audio_params.lock()
         {
-            if let Ok(mut params) = self.audio_params.lock() {
-                params.overdrive_enabled = self.overdrive_enabled;
-            }
+            params.overdrive_enabled = self.overdrive_enabled;
+        }
+        if self.overdrive_enabled && self.nam_enabled && self.nam_model_name.is_some() {
+            ui.label("Bypassed while an amp model is active");
         }
         if self.overdrive_enabled {
             ui.horizontal(|ui| {
@@ -268,34 +631,66 @@ impl GooseDsp {
                         .with_label("Gain", egui_knob::LabelPosition::Bottom),
                     )
                     .changed()
+                    && let Ok(mut params) = self.audio_params.lock()
                 {
-                    if let Ok(mut params) = self.audio_params.lock() {
-                        params.overdrive_gain = self.overdrive_gain;
-                    }
+                    params.overdrive_gain = self.overdrive_gain;
                 }
             });
             ui.add_space(15.0);
         }
 
-        if ui.checkbox(&mut self.cabinet_enabled, "Cabinet").changed() {
-            if let Ok(mut params) = self.audio_params.lock() {
-                params.cabinet_enabled = self.cabinet_enabled;
-            }
+        if ui.checkbox(&mut self.cabinet_enabled, "Cabinet").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.cabinet_enabled = self.cabinet_enabled;
         }
 
-        if ui.checkbox(&mut self.eq_enabled, "EQ").changed() {
-            if let Ok(mut params) = self.audio_params.lock() {
-                params.eq_enabled = self.eq_enabled;
-            }
+        if ui
+            .checkbox(&mut self.nam_enabled, "Amp Model (NAM)")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.nam_enabled = self.nam_enabled;
+        }
+        if self.nam_enabled {
+            self.nam_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.bitcrusher_enabled, "Bitcrusher")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.bitcrusher_enabled = self.bitcrusher_enabled;
+        }
+        if self.bitcrusher_enabled {
+            self.bitcrusher_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.volume_pedal_enabled, "Volume Pedal")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.volume_pedal_enabled = self.volume_pedal_enabled;
+        }
+        if self.volume_pedal_enabled {
+            self.volume_pedal_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.eq_enabled, "EQ").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.eq_enabled = self.eq_enabled;
         }
         if self.eq_enabled {
             self.eq_settings_ui(ui);
         }
 
-        if ui.checkbox(&mut self.gate_enabled, "Noise Gate").changed() {
-            if let Ok(mut params) = self.audio_params.lock() {
-                params.gate_enabled = self.gate_enabled;
-            }
+        if ui.checkbox(&mut self.gate_enabled, "Noise Gate").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.gate_enabled = self.gate_enabled;
         }
         if self.gate_enabled {
             ui.horizontal(|ui| {
@@ -311,81 +706,1388 @@ impl GooseDsp {
                         .with_label("Threshold", egui_knob::LabelPosition::Bottom),
                     )
                     .changed()
+                    && let Ok(mut params) = self.audio_params.lock()
                 {
-                    if let Ok(mut params) = self.audio_params.lock() {
-                        params.gate_threshold = self.gate_threshold;
-                    }
+                    params.gate_threshold = self.gate_threshold;
                 }
                 ui.add_space(30.0);
             });
         }
-    }
 
-    fn eq_settings_ui(&mut self, ui: &mut egui::Ui) {
-        ui.horizontal(|ui| {
-            ui.vertical(|ui| {
-                if ui
-                    .add(
-                        Knob::new(&mut self.eq_low, 0.0, 4.0, egui_knob::KnobStyle::Wiper)
-                            .with_size(30.0)
-                            .with_label("Low", egui_knob::LabelPosition::Bottom),
-                    )
-                    .changed()
+        if ui.checkbox(&mut self.swell_enabled, "Slow Gear").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.swell_enabled = self.swell_enabled;
+        }
+        if self.swell_enabled {
+            ui.horizontal(|ui| {
+                if effect_knob(ui, &mut self.swell_rise, 0.05, 3.0, "Rise")
+                    && let Ok(mut params) = self.audio_params.lock()
                 {
-                    if let Ok(mut params) = self.audio_params.lock() {
-                        params.eq_low = self.eq_low;
-                    }
+                    params.swell_rise = self.swell_rise;
                 }
-            });
-            ui.vertical(|ui| {
-                if ui
-                    .add(
-                        Knob::new(&mut self.eq_mid, 0.0, 4.0, egui_knob::KnobStyle::Wiper)
-                            .with_size(30.0)
-                            .with_label("Mid", egui_knob::LabelPosition::Bottom),
-                    )
-                    .changed()
+                if effect_knob(ui, &mut self.swell_sensitivity, 0.0, 1.0, "Sensitivity")
+                    && let Ok(mut params) = self.audio_params.lock()
                 {
-                    if let Ok(mut params) = self.audio_params.lock() {
-                        params.eq_mid = self.eq_mid;
-                    }
+                    params.swell_sensitivity = self.swell_sensitivity;
                 }
             });
-            ui.vertical(|ui| {
-                if ui
-                    .add(
-                        Knob::new(&mut self.eq_high, 0.0, 4.0, egui_knob::KnobStyle::Wiper)
-                            .with_size(30.0)
-                            .with_label("High", egui_knob::LabelPosition::Bottom),
-                    )
-                    .changed()
+            ui.add_space(15.0);
+        }
+
+        if ui.checkbox(&mut self.wah_enabled, "Wah").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.wah_enabled = self.wah_enabled;
+        }
+        if self.wah_enabled {
+            self.wah_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.envelope_filter_enabled, "Envelope Filter")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.envelope_filter_enabled = self.envelope_filter_enabled;
+        }
+        if self.envelope_filter_enabled {
+            self.envelope_filter_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.octaver_enabled, "Octaver").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.octaver_enabled = self.octaver_enabled;
+        }
+        if self.octaver_enabled {
+            self.octaver_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.pitch_enabled, "Pitch Shifter")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.pitch_enabled = self.pitch_enabled;
+        }
+        if self.pitch_enabled {
+            self.pitch_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.ring_mod_enabled, "Ring Modulator")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.ring_mod_enabled = self.ring_mod_enabled;
+        }
+        if self.ring_mod_enabled {
+            self.ring_mod_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.chorus_enabled, "Chorus").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.chorus_enabled = self.chorus_enabled;
+        }
+        if self.chorus_enabled {
+            self.chorus_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.vibrato_enabled, "Vibrato").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.vibrato_enabled = self.vibrato_enabled;
+        }
+        if self.vibrato_enabled {
+            ui.horizontal(|ui| {
+                if effect_knob(ui, &mut self.vibrato_rate, 0.5, 12.0, "Rate")
+                    && let Ok(mut params) = self.audio_params.lock()
                 {
-                    if let Ok(mut params) = self.audio_params.lock() {
-                        params.eq_high = self.eq_high;
-                    }
+                    params.vibrato_rate = self.vibrato_rate;
                 }
-            });
-        });
-        ui.add_space(15.0);
-    }
-
-    fn combo_box_audio_device(&mut self, ui: &mut egui::Ui) {
-        let previous_device = self.selected_device.clone();
-        egui::ComboBox::from_id_salt("audio_device")
-            .selected_text(self.selected_device.clone().unwrap_or_default())
-            .width(350.0)
-            .show_ui(ui, |ui| {
-                for device in &self.available_devices {
-                    ui.selectable_value(&mut self.selected_device, Some(device.clone()), device);
+                if effect_knob(ui, &mut self.vibrato_depth, 0.0, 5.0, "Depth")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.vibrato_depth = self.vibrato_depth;
                 }
             });
-        if self.selected_device != previous_device {
-            self.set_stream();
-            self.save_settings();
+            ui.add_space(15.0);
         }
-    }
 
-    fn combo_box_input_channel(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.univibe_enabled, "Uni-Vibe").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.univibe_enabled = self.univibe_enabled;
+        }
+        if self.univibe_enabled {
+            self.univibe_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.flanger_enabled, "Flanger").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.flanger_enabled = self.flanger_enabled;
+        }
+        if self.flanger_enabled {
+            self.flanger_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.phaser_enabled, "Phaser").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.phaser_enabled = self.phaser_enabled;
+        }
+        if self.phaser_enabled {
+            self.phaser_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.tremolo_enabled, "Tremolo").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.tremolo_enabled = self.tremolo_enabled;
+        }
+        if self.tremolo_enabled {
+            self.tremolo_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.rotary_enabled, "Rotary").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.rotary_enabled = self.rotary_enabled;
+        }
+        if self.rotary_enabled {
+            ui.horizontal(|ui| {
+                if ui.checkbox(&mut self.rotary_fast, "Fast (S)").changed() {
+                    self.set_rotary_fast(self.rotary_fast);
+                }
+                if effect_knob(ui, &mut self.rotary_spread, 0.0, 1.0, "Spread")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.rotary_spread = self.rotary_spread;
+                }
+            });
+            ui.add_space(15.0);
+        }
+
+        if ui
+            .checkbox(&mut self.tape_echo_enabled, "Tape Echo")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.tape_echo_enabled = self.tape_echo_enabled;
+        }
+        if self.tape_echo_enabled {
+            self.tape_echo_settings_ui(ui);
+        }
+
+        if ui.checkbox(&mut self.bbd_enabled, "Analog Delay").changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.bbd_enabled = self.bbd_enabled;
+        }
+        if self.bbd_enabled {
+            self.bbd_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.stereo_delay_enabled, "Stereo Delay")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.stereo_delay_enabled = self.stereo_delay_enabled;
+        }
+        if self.stereo_delay_enabled {
+            self.stereo_delay_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.spring_enabled, "Spring Reverb")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.spring_enabled = self.spring_enabled;
+        }
+        if self.spring_enabled {
+            ui.horizontal(|ui| {
+                if effect_knob(ui, &mut self.spring_dwell, 0.0, 1.0, "Dwell")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.spring_dwell = self.spring_dwell;
+                }
+                if effect_knob(ui, &mut self.spring_tone, 0.0, 1.0, "Tone")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.spring_tone = self.spring_tone;
+                }
+                if effect_knob(ui, &mut self.spring_mix, 0.0, 1.0, "Mix")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.spring_mix = self.spring_mix;
+                }
+            });
+            ui.add_space(15.0);
+        }
+
+        if ui
+            .checkbox(&mut self.shimmer_enabled, "Shimmer Reverb")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.shimmer_enabled = self.shimmer_enabled;
+        }
+        if self.shimmer_enabled {
+            self.shimmer_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.convolution_enabled, "Convolution Reverb")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.convolution_enabled = self.convolution_enabled;
+        }
+        if self.convolution_enabled {
+            self.convolution_settings_ui(ui);
+        }
+
+        if ui
+            .checkbox(&mut self.duck_enabled, "Duck Delays & Reverbs")
+            .changed()
+            && let Ok(mut params) = self.audio_params.lock()
+        {
+            params.duck_enabled = self.duck_enabled;
+        }
+        if self.duck_enabled {
+            ui.horizontal(|ui| {
+                if effect_knob(ui, &mut self.duck_threshold, -60.0, 0.0, "Threshold")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.duck_threshold = self.duck_threshold;
+                }
+                if effect_knob(ui, &mut self.duck_amount, 0.0, 1.0, "Amount")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.duck_amount = self.duck_amount;
+                }
+                if effect_knob(ui, &mut self.duck_release, 0.05, 2.0, "Release")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.duck_release = self.duck_release;
+                }
+            });
+            ui.add_space(15.0);
+        }
+    }
+
+    fn wah_settings_ui(&mut self, ui: &mut egui::Ui) {
+        // A connected expression pedal moves the position behind our back
+        if self.midi_connection.is_some()
+            && let Ok(params) = self.audio_params.lock()
+        {
+            self.wah_position = params.wah_position;
+        }
+
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::Slider::new(&mut self.wah_position, 0.0..=1.0)
+                        .vertical()
+                        .show_value(false)
+                        .text("Pedal"),
+                )
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.wah_position = self.wah_position;
+            }
+
+            ui.vertical(|ui| {
+                let previous_range = self.wah_range;
+                egui::ComboBox::from_id_salt("wah_range")
+                    .selected_text(self.wah_range.name())
+                    .show_ui(ui, |ui| {
+                        for range in WahRange::ALL {
+                            ui.selectable_value(&mut self.wah_range, range, range.name());
+                        }
+                    });
+                if self.wah_range != previous_range
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.wah_range = self.wah_range;
+                }
+
+                if effect_knob(ui, &mut self.wah_q, 1.0, 15.0, "Q")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.wah_q = self.wah_q;
+                }
+
+                ui.horizontal(|ui| {
+                    let previous_port = self.selected_midi_port.clone();
+                    egui::ComboBox::from_id_salt("midi_port")
+                        .selected_text(
+                            self.selected_midi_port
+                                .clone()
+                                .unwrap_or("No MIDI input".to_string()),
+                        )
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.selected_midi_port, None, "None");
+                            for port in &self.midi_ports {
+                                ui.selectable_value(
+                                    &mut self.selected_midi_port,
+                                    Some(port.clone()),
+                                    port,
+                                );
+                            }
+                        });
+                    if self.selected_midi_port != previous_port {
+                        self.connect_midi();
+                    }
+                    if ui.button("⟳").clicked() {
+                        self.refresh_midi_ports();
+                    }
+
+                    ui.label("CC:");
+                    if ui
+                        .add(egui::DragValue::new(&mut self.wah_cc).range(0..=127))
+                        .changed()
+                        && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.wah_cc = self.wah_cc;
+                    }
+                });
+            });
+        });
+        ui.add_space(15.0);
+    }
+
+    fn envelope_filter_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_mode = self.envelope_filter_mode;
+            ui.selectable_value(
+                &mut self.envelope_filter_mode,
+                FilterMode::BandPass,
+                "Band-pass",
+            );
+            ui.selectable_value(
+                &mut self.envelope_filter_mode,
+                FilterMode::LowPass,
+                "Low-pass",
+            );
+            if self.envelope_filter_mode != previous_mode
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.envelope_filter_mode = self.envelope_filter_mode;
+            }
+
+            if ui
+                .checkbox(&mut self.envelope_filter_down, "Sweep down")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.envelope_filter_down = self.envelope_filter_down;
+            }
+            if ui
+                .checkbox(&mut self.envelope_filter_lfo, "Auto-wah")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.envelope_filter_lfo = self.envelope_filter_lfo;
+            }
+        });
+        ui.horizontal(|ui| {
+            if self.envelope_filter_lfo {
+                if effect_knob(ui, &mut self.envelope_filter_rate, 0.1, 10.0, "Rate")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.envelope_filter_rate = self.envelope_filter_rate;
+                }
+            } else {
+                if effect_knob(
+                    ui,
+                    &mut self.envelope_filter_sensitivity,
+                    0.0,
+                    1.0,
+                    "Sensitivity",
+                ) && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.envelope_filter_sensitivity = self.envelope_filter_sensitivity;
+                }
+                if effect_knob(ui, &mut self.envelope_filter_attack, 1.0, 50.0, "Attack")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.envelope_filter_attack = self.envelope_filter_attack;
+                }
+                if effect_knob(ui, &mut self.envelope_filter_decay, 20.0, 500.0, "Decay")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.envelope_filter_decay = self.envelope_filter_decay;
+                }
+            }
+            if effect_knob(ui, &mut self.envelope_filter_range, 1.0, 5.0, "Range")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.envelope_filter_range = self.envelope_filter_range;
+            }
+            if effect_knob(ui, &mut self.envelope_filter_q, 0.5, 10.0, "Q")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.envelope_filter_q = self.envelope_filter_q;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn octaver_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.octaver_dry, 0.0, 1.0, "Dry")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.octaver_dry = self.octaver_dry;
+            }
+            if effect_knob(ui, &mut self.octaver_sub, 0.0, 2.0, "-1 Oct")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.octaver_sub = self.octaver_sub;
+            }
+            if effect_knob(ui, &mut self.octaver_sub2, 0.0, 2.0, "-2 Oct")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.octaver_sub2 = self.octaver_sub2;
+            }
+            if effect_knob(ui, &mut self.octaver_up, 0.0, 2.0, "+1 Oct")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.octaver_up = self.octaver_up;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn pitch_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_mode = self.pitch_mode;
+            ui.selectable_value(&mut self.pitch_mode, PitchMode::Shift, "Shift");
+            ui.selectable_value(&mut self.pitch_mode, PitchMode::Drop, "Drop tune");
+            ui.selectable_value(&mut self.pitch_mode, PitchMode::Harmony, "Harmonizer");
+            if self.pitch_mode != previous_mode
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.pitch_mode = self.pitch_mode;
+            }
+        });
+
+        ui.horizontal(|ui| match self.pitch_mode {
+            PitchMode::Shift => {
+                ui.label("Semitones:");
+                if ui
+                    .add(egui::DragValue::new(&mut self.pitch_semitones).range(-24..=24))
+                    .changed()
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_semitones = self.pitch_semitones;
+                }
+                if effect_knob(ui, &mut self.pitch_cents, -50.0, 50.0, "Cents")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_cents = self.pitch_cents;
+                }
+                if effect_knob(ui, &mut self.pitch_mix, 0.0, 1.0, "Mix")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_mix = self.pitch_mix;
+                }
+            }
+            PitchMode::Drop => {
+                let previous_drop = self.pitch_drop;
+                egui::ComboBox::from_id_salt("pitch_drop")
+                    .selected_text(format!("-{} semitones", self.pitch_drop))
+                    .show_ui(ui, |ui| {
+                        for drop in 1..=12 {
+                            ui.selectable_value(
+                                &mut self.pitch_drop,
+                                drop,
+                                format!("-{} semitones", drop),
+                            );
+                        }
+                    });
+                if self.pitch_drop != previous_drop
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_drop = self.pitch_drop;
+                }
+            }
+            PitchMode::Harmony => {
+                let previous_key = self.pitch_key;
+                egui::ComboBox::from_id_salt("pitch_key")
+                    .selected_text(NOTE_NAMES[self.pitch_key])
+                    .show_ui(ui, |ui| {
+                        for (key, name) in NOTE_NAMES.iter().enumerate() {
+                            ui.selectable_value(&mut self.pitch_key, key, *name);
+                        }
+                    });
+                if self.pitch_key != previous_key
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_key = self.pitch_key;
+                }
+
+                let previous_scale = self.pitch_scale;
+                egui::ComboBox::from_id_salt("pitch_scale")
+                    .selected_text(self.pitch_scale.name())
+                    .show_ui(ui, |ui| {
+                        for scale in Scale::ALL {
+                            ui.selectable_value(&mut self.pitch_scale, scale, scale.name());
+                        }
+                    });
+                if self.pitch_scale != previous_scale
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_scale = self.pitch_scale;
+                }
+
+                let previous_steps = self.pitch_steps;
+                let selected = HARMONY_INTERVALS
+                    .iter()
+                    .find(|(_, steps)| *steps == self.pitch_steps)
+                    .map_or("", |(label, _)| label);
+                egui::ComboBox::from_id_salt("pitch_steps")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (label, steps) in HARMONY_INTERVALS {
+                            ui.selectable_value(&mut self.pitch_steps, steps, label);
+                        }
+                    });
+                if self.pitch_steps != previous_steps
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_steps = self.pitch_steps;
+                }
+
+                if effect_knob(ui, &mut self.pitch_mix, 0.0, 1.0, "Mix")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.pitch_mix = self.pitch_mix;
+                }
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn chorus_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.chorus_rate, 0.1, 5.0, "Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.chorus_rate = self.chorus_rate;
+            }
+            if effect_knob(ui, &mut self.chorus_depth, 0.0, 5.0, "Depth")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.chorus_depth = self.chorus_depth;
+            }
+            if effect_knob(ui, &mut self.chorus_delay, 5.0, 30.0, "Delay")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.chorus_delay = self.chorus_delay;
+            }
+            if effect_knob(ui, &mut self.chorus_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.chorus_mix = self.chorus_mix;
+            }
+            if effect_knob(ui, &mut self.chorus_spread, 0.0, 1.0, "Spread")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.chorus_spread = self.chorus_spread;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn univibe_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_mode = self.univibe_mode;
+            ui.selectable_value(&mut self.univibe_mode, VibeMode::Chorus, "Chorus");
+            ui.selectable_value(&mut self.univibe_mode, VibeMode::Vibrato, "Vibrato");
+            if self.univibe_mode != previous_mode
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.univibe_mode = self.univibe_mode;
+            }
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.univibe_rate, 0.5, 10.0, "Speed")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.univibe_rate = self.univibe_rate;
+            }
+            if effect_knob(ui, &mut self.univibe_depth, 0.0, 1.0, "Intensity")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.univibe_depth = self.univibe_depth;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn flanger_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.flanger_rate, 0.05, 5.0, "Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.flanger_rate = self.flanger_rate;
+            }
+            if effect_knob(ui, &mut self.flanger_depth, 0.0, 1.0, "Depth")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.flanger_depth = self.flanger_depth;
+            }
+            if effect_knob(ui, &mut self.flanger_manual, 0.1, 10.0, "Manual")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.flanger_manual = self.flanger_manual;
+            }
+            if effect_knob(ui, &mut self.flanger_feedback, -0.95, 0.95, "Feedback")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.flanger_feedback = self.flanger_feedback;
+            }
+            if effect_knob(ui, &mut self.flanger_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.flanger_mix = self.flanger_mix;
+            }
+            if ui
+                .checkbox(&mut self.flanger_through_zero, "Through-zero")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.flanger_through_zero = self.flanger_through_zero;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn phaser_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_stages = self.phaser_stages;
+            egui::ComboBox::from_id_salt("phaser_stages")
+                .selected_text(format!("{} stages", self.phaser_stages))
+                .show_ui(ui, |ui| {
+                    for stages in STAGE_OPTIONS {
+                        ui.selectable_value(
+                            &mut self.phaser_stages,
+                            stages,
+                            format!("{} stages", stages),
+                        );
+                    }
+                });
+            if self.phaser_stages != previous_stages
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_stages = self.phaser_stages;
+            }
+
+            if ui.checkbox(&mut self.phaser_sync, "Sync").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_sync = self.phaser_sync;
+            }
+            if self.phaser_sync
+                && division_combo_box(ui, "phaser_division", &mut self.phaser_division)
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_division = self.phaser_division;
+            }
+        });
+        ui.horizontal(|ui| {
+            if !self.phaser_sync
+                && effect_knob(ui, &mut self.phaser_rate, 0.05, 5.0, "Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_rate = self.phaser_rate;
+            }
+            if effect_knob(ui, &mut self.phaser_depth, 0.0, 1.0, "Depth")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_depth = self.phaser_depth;
+            }
+            if effect_knob(ui, &mut self.phaser_feedback, -0.95, 0.95, "Feedback")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_feedback = self.phaser_feedback;
+            }
+            if effect_knob(ui, &mut self.phaser_centre, 200.0, 4000.0, "Centre")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_centre = self.phaser_centre;
+            }
+            if effect_knob(ui, &mut self.phaser_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.phaser_mix = self.phaser_mix;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn set_rotary_fast(&mut self, fast: bool) {
+        self.rotary_fast = fast;
+        if let Ok(mut params) = self.audio_params.lock() {
+            params.rotary_fast = fast;
+        }
+    }
+
+    fn tremolo_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_shape = self.tremolo_shape;
+            egui::ComboBox::from_id_salt("tremolo_shape")
+                .selected_text(self.tremolo_shape.name())
+                .show_ui(ui, |ui| {
+                    for shape in LfoShape::ALL {
+                        ui.selectable_value(&mut self.tremolo_shape, shape, shape.name());
+                    }
+                });
+            if self.tremolo_shape != previous_shape
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_shape = self.tremolo_shape;
+            }
+
+            if ui.checkbox(&mut self.tremolo_sync, "Sync").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_sync = self.tremolo_sync;
+            }
+            if self.tremolo_sync
+                && division_combo_box(ui, "tremolo_division", &mut self.tremolo_division)
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_division = self.tremolo_division;
+            }
+            if ui.checkbox(&mut self.tremolo_pan, "Pan").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_pan = self.tremolo_pan;
+            }
+            if ui
+                .checkbox(&mut self.tremolo_harmonic, "Harmonic")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_harmonic = self.tremolo_harmonic;
+            }
+        });
+        ui.horizontal(|ui| {
+            if !self.tremolo_sync
+                && effect_knob(ui, &mut self.tremolo_rate, 0.5, 15.0, "Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_rate = self.tremolo_rate;
+            }
+            if effect_knob(ui, &mut self.tremolo_depth, 0.0, 1.0, "Depth")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tremolo_depth = self.tremolo_depth;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn ring_mod_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_shape = self.ring_mod_shape;
+            for shape in LfoShape::ALL {
+                ui.selectable_value(&mut self.ring_mod_shape, shape, shape.name());
+            }
+            if self.ring_mod_shape != previous_shape
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.ring_mod_shape = self.ring_mod_shape;
+            }
+            if ui
+                .checkbox(&mut self.ring_mod_tracking, "Track pitch")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.ring_mod_tracking = self.ring_mod_tracking;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add_enabled_ui(!self.ring_mod_tracking, |ui| {
+                if effect_knob(ui, &mut self.ring_mod_frequency, 20.0, 2000.0, "Freq")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.ring_mod_frequency = self.ring_mod_frequency;
+                }
+            });
+            if effect_knob(ui, &mut self.ring_mod_fine, -100.0, 100.0, "Fine")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.ring_mod_fine = self.ring_mod_fine;
+            }
+            if effect_knob(ui, &mut self.ring_mod_lfo_rate, 0.05, 10.0, "LFO Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.ring_mod_lfo_rate = self.ring_mod_lfo_rate;
+            }
+            if effect_knob(ui, &mut self.ring_mod_lfo_depth, 0.0, 1.0, "LFO Depth")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.ring_mod_lfo_depth = self.ring_mod_lfo_depth;
+            }
+            if effect_knob(ui, &mut self.ring_mod_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.ring_mod_mix = self.ring_mod_mix;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn convolution_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Load IR...").clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("WAV impulse response", &["wav"])
+                    .pick_file()
+            {
+                match ImpulseResponse::load(&path) {
+                    Ok(ir) => {
+                        self.convolution_ir = Some(ir);
+                        self.update_convolution_kernel();
+                    }
+                    Err(err) => self.error_message = Some(err),
+                }
+            }
+            ui.label(
+                self.convolution_ir
+                    .as_ref()
+                    .map_or("No IR loaded", |ir| ir.name.as_str()),
+            );
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(
+                ui,
+                &mut self.convolution_pre_delay,
+                0.0,
+                MAX_PRE_DELAY_MS,
+                "Pre-delay",
+            ) && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.convolution_pre_delay = self.convolution_pre_delay;
+            }
+            // Rebuilding the kernel is too heavy to do every frame of a drag
+            if effect_knob_response(ui, &mut self.convolution_decay, 0.05, 1.0, "Decay")
+                .drag_stopped()
+            {
+                self.update_convolution_kernel();
+            }
+            if effect_knob(ui, &mut self.convolution_width, 0.0, 2.0, "Width")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.convolution_width = self.convolution_width;
+            }
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.convolution_low_cut, 20.0, 1000.0, "Low Cut")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.convolution_low_cut = self.convolution_low_cut;
+            }
+            if effect_knob(
+                ui,
+                &mut self.convolution_high_cut,
+                1000.0,
+                20000.0,
+                "High Cut",
+            ) && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.convolution_high_cut = self.convolution_high_cut;
+            }
+            if effect_knob(ui, &mut self.convolution_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.convolution_mix = self.convolution_mix;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    // The kernel and the reverb that runs it are both built here, so the
+    // audio thread only swaps the new reverb in, carrying the tail across, and
+    // hands the old one back to be freed on this thread.
+    pub fn update_convolution_kernel(&mut self) {
+        let sample_rate = self.selected_sample_rate as f32;
+        let kernel = self.convolution_ir.as_ref().map(|ir| {
+            Arc::new(ConvolutionKernel::new(
+                ir,
+                sample_rate,
+                self.convolution_decay,
+            ))
+        });
+        let reverb = kernel
+            .as_ref()
+            .map(|kernel| ConvolutionReverb::new(Arc::clone(kernel), sample_rate));
+        let retired = self.audio_params.lock().ok().and_then(|mut params| {
+            params.convolution_kernel = kernel;
+            std::mem::replace(&mut params.convolution_reverb, reverb)
+        });
+        drop(retired);
+    }
+
+    fn shimmer_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Interval");
+            let previous_interval = self.shimmer_interval;
+            for interval in ShimmerInterval::ALL {
+                ui.selectable_value(&mut self.shimmer_interval, interval, interval.name());
+            }
+            if self.shimmer_interval != previous_interval
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.shimmer_interval = self.shimmer_interval;
+            }
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.shimmer_amount, 0.0, 1.0, "Shimmer")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.shimmer_amount = self.shimmer_amount;
+            }
+            if effect_knob(ui, &mut self.shimmer_decay, 0.5, 20.0, "Decay")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.shimmer_decay = self.shimmer_decay;
+            }
+            if effect_knob(ui, &mut self.shimmer_tone, 0.0, 1.0, "Tone")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.shimmer_tone = self.shimmer_tone;
+            }
+            if effect_knob(ui, &mut self.shimmer_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.shimmer_mix = self.shimmer_mix;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn stereo_delay_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let previous_mode = self.stereo_delay_mode;
+            for mode in DelayMode::ALL {
+                ui.selectable_value(&mut self.stereo_delay_mode, mode, mode.name());
+            }
+            if self.stereo_delay_mode != previous_mode
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.stereo_delay_mode = self.stereo_delay_mode;
+            }
+            if ui.checkbox(&mut self.stereo_delay_sync, "Sync").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.stereo_delay_sync = self.stereo_delay_sync;
+            }
+        });
+
+        if self.stereo_delay_mode == DelayMode::MultiTap {
+            self.multi_tap_ui(ui);
+        } else {
+            ui.horizontal(|ui| {
+                if self.stereo_delay_sync {
+                    ui.label("L");
+                    if division_combo_box(
+                        ui,
+                        "stereo_delay_left_division",
+                        &mut self.stereo_delay_left_division,
+                    ) && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.stereo_delay_left_division = self.stereo_delay_left_division;
+                    }
+                    ui.label("R");
+                    if division_combo_box(
+                        ui,
+                        "stereo_delay_right_division",
+                        &mut self.stereo_delay_right_division,
+                    ) && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.stereo_delay_right_division = self.stereo_delay_right_division;
+                    }
+                } else {
+                    if effect_knob(
+                        ui,
+                        &mut self.stereo_delay_left,
+                        1.0,
+                        stereo_delay::MAX_TIME_MS,
+                        "Left",
+                    ) && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.stereo_delay_left = self.stereo_delay_left;
+                    }
+                    if effect_knob(
+                        ui,
+                        &mut self.stereo_delay_right,
+                        1.0,
+                        stereo_delay::MAX_TIME_MS,
+                        "Right",
+                    ) && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.stereo_delay_right = self.stereo_delay_right;
+                    }
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.stereo_delay_feedback, 0.0, 0.98, "Feedback")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.stereo_delay_feedback = self.stereo_delay_feedback;
+            }
+            if effect_knob(ui, &mut self.stereo_delay_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.stereo_delay_mix = self.stereo_delay_mix;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn multi_tap_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Taps");
+            if ui
+                .add(egui::DragValue::new(&mut self.stereo_delay_taps).range(1..=MAX_TAPS))
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.stereo_delay_taps = self.stereo_delay_taps;
+            }
+        });
+
+        for i in 0..self.stereo_delay_taps {
+            ui.horizontal(|ui| {
+                ui.label(format!("{}", i + 1));
+                if self.stereo_delay_sync {
+                    if division_combo_box(
+                        ui,
+                        &format!("stereo_delay_tap_division_{}", i),
+                        &mut self.stereo_delay_tap_divisions[i],
+                    ) && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.stereo_delay_tap_divisions[i] = self.stereo_delay_tap_divisions[i];
+                    }
+                } else if effect_knob(
+                    ui,
+                    &mut self.stereo_delay_tap_times[i],
+                    1.0,
+                    stereo_delay::MAX_TIME_MS,
+                    "Time",
+                ) && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.stereo_delay_tap_times[i] = self.stereo_delay_tap_times[i];
+                }
+                if effect_knob(ui, &mut self.stereo_delay_tap_levels[i], 0.0, 1.0, "Level")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.stereo_delay_tap_levels[i] = self.stereo_delay_tap_levels[i];
+                }
+                if effect_knob(ui, &mut self.stereo_delay_tap_pans[i], -1.0, 1.0, "Pan")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.stereo_delay_tap_pans[i] = self.stereo_delay_tap_pans[i];
+                }
+            });
+        }
+    }
+
+    fn bbd_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.bbd_time, 20.0, 550.0, "Time")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bbd_time = self.bbd_time;
+            }
+            if effect_knob(ui, &mut self.bbd_feedback, 0.0, 1.05, "Feedback")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bbd_feedback = self.bbd_feedback;
+            }
+            if effect_knob(ui, &mut self.bbd_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bbd_mix = self.bbd_mix;
+            }
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.bbd_mod_rate, 0.1, 5.0, "Mod Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bbd_mod_rate = self.bbd_mod_rate;
+            }
+            if effect_knob(ui, &mut self.bbd_mod_depth, 0.0, 1.0, "Mod Depth")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bbd_mod_depth = self.bbd_mod_depth;
+            }
+            if effect_knob(ui, &mut self.bbd_noise, 0.0, 1.0, "Noise")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bbd_noise = self.bbd_noise;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn tape_echo_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Heads");
+            let previous_heads = self.tape_echo_heads;
+            egui::ComboBox::from_id_salt("tape_echo_heads")
+                .selected_text(HEAD_COMBOS[self.tape_echo_heads].0)
+                .show_ui(ui, |ui| {
+                    for (i, (label, _)) in HEAD_COMBOS.iter().enumerate() {
+                        ui.selectable_value(&mut self.tape_echo_heads, i, *label);
+                    }
+                });
+            if self.tape_echo_heads != previous_heads
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_heads = self.tape_echo_heads;
+            }
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.tape_echo_time, 20.0, 600.0, "Repeat")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_time = self.tape_echo_time;
+            }
+            if effect_knob(ui, &mut self.tape_echo_feedback, 0.0, 1.1, "Intensity")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_feedback = self.tape_echo_feedback;
+            }
+            if effect_knob(ui, &mut self.tape_echo_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_mix = self.tape_echo_mix;
+            }
+        });
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.tape_echo_saturation, 0.0, 1.0, "Saturation")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_saturation = self.tape_echo_saturation;
+            }
+            if effect_knob(ui, &mut self.tape_echo_tone, 0.0, 1.0, "Tone")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_tone = self.tape_echo_tone;
+            }
+            if effect_knob(ui, &mut self.tape_echo_wow, 0.0, 1.0, "Wow")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_wow = self.tape_echo_wow;
+            }
+            if effect_knob(ui, &mut self.tape_echo_flutter, 0.0, 1.0, "Flutter")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.tape_echo_flutter = self.tape_echo_flutter;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn bitcrusher_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if effect_knob(ui, &mut self.bitcrusher_bits, 1.0, 24.0, "Bits")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bitcrusher_bits = self.bitcrusher_bits;
+            }
+            let max_rate = self.selected_sample_rate as f32;
+            if effect_knob(ui, &mut self.bitcrusher_rate, 500.0, max_rate, "Rate")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bitcrusher_rate = self.bitcrusher_rate;
+            }
+            if effect_knob(ui, &mut self.bitcrusher_mix, 0.0, 1.0, "Mix")
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bitcrusher_mix = self.bitcrusher_mix;
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.bitcrusher_anti_alias, "Anti-alias")
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bitcrusher_anti_alias = self.bitcrusher_anti_alias;
+            }
+            if ui.checkbox(&mut self.bitcrusher_dither, "Dither").changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.bitcrusher_dither = self.bitcrusher_dither;
+            }
+        });
+        ui.add_space(15.0);
+    }
+
+    fn volume_pedal_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .add(
+                    egui::Slider::new(&mut self.volume_pedal_position, 0.0..=1.0)
+                        .vertical()
+                        .show_value(false)
+                        .text("Pedal"),
+                )
+                .changed()
+                && let Ok(mut params) = self.audio_params.lock()
+            {
+                params.volume_pedal_position = self.volume_pedal_position;
+            }
+            ui.vertical(|ui| {
+                ui.horizontal(|ui| {
+                    let previous_taper = self.volume_pedal_taper;
+                    ui.selectable_value(&mut self.volume_pedal_taper, Taper::Linear, "Linear");
+                    ui.selectable_value(&mut self.volume_pedal_taper, Taper::Audio, "Audio");
+                    if self.volume_pedal_taper != previous_taper
+                        && let Ok(mut params) = self.audio_params.lock()
+                    {
+                        params.volume_pedal_taper = self.volume_pedal_taper;
+                    }
+                });
+                if effect_knob(ui, &mut self.volume_pedal_minimum, 0.0, 1.0, "Minimum")
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.volume_pedal_minimum = self.volume_pedal_minimum;
+                }
+            });
+        });
+        ui.add_space(15.0);
+    }
+
+    fn nam_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Load model...").clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("NAM model", &["nam"])
+                    .pick_file()
+            {
+                match NamModel::load(&path) {
+                    Ok(model) => {
+                        self.error_message = model
+                            .sample_rate
+                            .filter(|&rate| rate as u32 != self.selected_sample_rate)
+                            .map(|rate| {
+                                format!(
+                                    "Model expects {} Hz, stream runs at {} Hz",
+                                    rate, self.selected_sample_rate
+                                )
+                            });
+                        self.nam_model_name = Some(model.name.clone());
+                        let model = Arc::new(model);
+                        let processor = NamProcessor::new(Arc::clone(&model));
+                        let retired = self.audio_params.lock().ok().and_then(|mut params| {
+                            params.nam_model = Some(model);
+                            params.nam_processor.replace(processor)
+                        });
+                        drop(retired);
+                    }
+                    Err(err) => self.error_message = Some(err),
+                }
+            }
+            ui.label(self.nam_model_name.as_deref().unwrap_or("No model loaded"));
+        });
+        ui.add_space(15.0);
+    }
+
+    // Layer histories are allocated here rather than on the audio thread, and
+    // whatever the audio thread hands back is freed here too.
+    pub fn update_nam_processor(&mut self) {
+        let model = self
+            .audio_params
+            .lock()
+            .ok()
+            .and_then(|params| params.nam_model.clone());
+        let processor = model.map(NamProcessor::new);
+        let retired = self
+            .audio_params
+            .lock()
+            .ok()
+            .and_then(|mut params| std::mem::replace(&mut params.nam_processor, processor));
+        drop(retired);
+    }
+
+    fn eq_settings_ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                if ui
+                    .add(
+                        Knob::new(&mut self.eq_low, 0.0, 4.0, egui_knob::KnobStyle::Wiper)
+                            .with_size(30.0)
+                            .with_label("Low", egui_knob::LabelPosition::Bottom),
+                    )
+                    .changed()
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.eq_low = self.eq_low;
+                }
+            });
+            ui.vertical(|ui| {
+                if ui
+                    .add(
+                        Knob::new(&mut self.eq_mid, 0.0, 4.0, egui_knob::KnobStyle::Wiper)
+                            .with_size(30.0)
+                            .with_label("Mid", egui_knob::LabelPosition::Bottom),
+                    )
+                    .changed()
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.eq_mid = self.eq_mid;
+                }
+            });
+            ui.vertical(|ui| {
+                if ui
+                    .add(
+                        Knob::new(&mut self.eq_high, 0.0, 4.0, egui_knob::KnobStyle::Wiper)
+                            .with_size(30.0)
+                            .with_label("High", egui_knob::LabelPosition::Bottom),
+                    )
+                    .changed()
+                    && let Ok(mut params) = self.audio_params.lock()
+                {
+                    params.eq_high = self.eq_high;
+                }
+            });
+        });
+        ui.add_space(15.0);
+    }
+
+    fn combo_box_audio_device(&mut self, ui: &mut egui::Ui) {
+        let previous_device = self.selected_device.clone();
+        egui::ComboBox::from_id_salt("audio_device")
+            .selected_text(self.selected_device.clone().unwrap_or_default())
+            .width(350.0)
+            .show_ui(ui, |ui| {
+                for device in &self.available_devices {
+                    ui.selectable_value(&mut self.selected_device, Some(device.clone()), device);
+                }
+            });
+        if self.selected_device != previous_device {
+            self.set_stream();
+            self.save_settings();
+        }
+    }
+
+    fn combo_box_input_channel(&mut self, ui: &mut egui::Ui) {
         let previous_channel = self.selected_input_channel;
         egui::ComboBox::from_id_salt("input_channel")
             .selected_text(format!("Channel {}", self.selected_input_channel + 1))
@@ -461,3 +2163,33 @@ impl GooseDsp {
         }
     }
 }
+
+fn effect_knob(ui: &mut egui::Ui, value: &mut f32, min: f32, max: f32, label: &str) -> bool {
+    effect_knob_response(ui, value, min, max, label).changed()
+}
+
+fn effect_knob_response(
+    ui: &mut egui::Ui,
+    value: &mut f32,
+    min: f32,
+    max: f32,
+    label: &str,
+) -> egui::Response {
+    ui.add(
+        Knob::new(value, min, max, egui_knob::KnobStyle::Wiper)
+            .with_size(30.0)
+            .with_label(label, egui_knob::LabelPosition::Bottom),
+    )
+}
+
+fn division_combo_box(ui: &mut egui::Ui, id: &str, division: &mut usize) -> bool {
+    let previous_division = *division;
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(DIVISIONS[*division].0)
+        .show_ui(ui, |ui| {
+            for (i, (label, _)) in DIVISIONS.iter().enumerate() {
+                ui.selectable_value(division, i, *label);
+            }
+        });
+    *division != previous_division
+}
@@ -1,4 +1,3 @@
-use hound;
 use std::fs::File;
 use std::io::BufWriter;
 